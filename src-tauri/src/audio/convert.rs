@@ -0,0 +1,87 @@
+use super::open_audio;
+use std::path::Path;
+use symphonia::core::codecs::CODEC_TYPE_PCM_S16LE;
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+use tempfile::NamedTempFile;
+
+/// Prefix on conversion errors, so the frontend can tell them apart from
+/// transcription failures.
+pub const AUDIO_CONVERT_ERROR: &str = "AudioConvertError";
+
+const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+fn convert_error(message: impl std::fmt::Display) -> String {
+    format!("{}: {}", AUDIO_CONVERT_ERROR, message)
+}
+
+/// whisper-cli reads 16kHz mono 16-bit WAV directly; anything else has to be
+/// converted first.
+pub fn is_whisper_compatible(path: &Path) -> bool {
+    let is_wav = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+    if !is_wav {
+        return false;
+    }
+
+    let Ok(format) = open_audio(path) else {
+        return false;
+    };
+    format.default_track().is_some_and(|track| {
+        let params = &track.codec_params;
+        params.codec == CODEC_TYPE_PCM_S16LE
+            && params.sample_rate == Some(WHISPER_SAMPLE_RATE)
+            && params.channels.map(|c| c.count()) == Some(1)
+    })
+}
+
+/// Converts to a temporary 16kHz mono WAV with the system `ffmpeg`. The file
+/// is deleted when the returned handle is dropped.
+pub async fn convert_for_whisper(app: &AppHandle, path: &Path) -> Result<NamedTempFile, String> {
+    let output_file = tempfile::Builder::new()
+        .prefix("whisper-gui-")
+        .suffix(".wav")
+        .tempfile()
+        .map_err(|e| convert_error(format!("Failed to create temporary file: {}", e)))?;
+
+    let output = app
+        .shell()
+        .command("ffmpeg")
+        .args(["-nostdin", "-y", "-v", "error", "-i"])
+        .arg(path.to_string_lossy().to_string())
+        .args(["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+        .arg(output_file.path().to_string_lossy().to_string())
+        .output()
+        .await
+        .map_err(|_| {
+            convert_error("ffmpeg is not available; install FFmpeg to transcribe this format")
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(convert_error(format!(
+            "ffmpeg could not convert '{}': {}",
+            path.display(),
+            stderr.trim()
+        )));
+    }
+
+    Ok(output_file)
+}
+
+/// Returns a converted copy when `path` can't be fed to whisper-cli as is.
+pub async fn prepare_for_whisper(
+    app: &AppHandle,
+    path: &Path,
+) -> Result<Option<NamedTempFile>, String> {
+    let check_path = path.to_path_buf();
+    let compatible = tokio::task::spawn_blocking(move || is_whisper_compatible(&check_path))
+        .await
+        .unwrap_or(false);
+    if compatible {
+        return Ok(None);
+    }
+    convert_for_whisper(app, path).await.map(Some)
+}
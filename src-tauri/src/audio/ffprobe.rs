@@ -0,0 +1,95 @@
+use super::AudioInfo;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+
+/// The `audio_info` result, which predates `AudioInfo`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioStreamInfo {
+    pub duration_secs: f64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub codec: String,
+}
+
+impl From<AudioInfo> for AudioStreamInfo {
+    fn from(info: AudioInfo) -> Self {
+        Self {
+            duration_secs: info.duration_ms as f64 / 1000.0,
+            sample_rate: info.sample_rate,
+            channels: info.channels,
+            codec: info.format,
+        }
+    }
+}
+
+/// Reads the first audio stream and container duration with the system
+/// `ffprobe`, which understands far more formats than the bundled decoders.
+pub async fn probe_audio_info(app: &AppHandle, path: &Path) -> Result<AudioInfo, String> {
+    let output = app
+        .shell()
+        .command("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "a:0",
+            "-show_entries",
+            "stream=codec_name,sample_rate,channels:format=duration,size",
+            "-of",
+            "json",
+        ])
+        .arg(path.to_string_lossy().to_string())
+        .output()
+        .await
+        .map_err(|_| {
+            "ffprobe is not available; install FFmpeg and make sure ffprobe is on your PATH"
+                .to_string()
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "ffprobe could not read the file: {}",
+            stderr.trim()
+        ));
+    }
+
+    parse_ffprobe_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// ffprobe reports most numbers as strings; either form is accepted.
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_ffprobe_output(json: &str) -> Result<AudioInfo, String> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid ffprobe output: {}", e))?;
+
+    let stream = value
+        .get("streams")
+        .and_then(Value::as_array)
+        .and_then(|streams| streams.first())
+        .ok_or("Unsupported file: no audio stream found")?;
+
+    Ok(AudioInfo {
+        duration_ms: number(value.pointer("/format/duration"))
+            .map(|secs| (secs * 1000.0) as u64)
+            .unwrap_or(0),
+        sample_rate: number(stream.get("sample_rate")).unwrap_or(0.0) as u32,
+        channels: number(stream.get("channels")).unwrap_or(0.0) as u16,
+        format: stream
+            .get("codec_name")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string(),
+        size_bytes: number(value.pointer("/format/size")).unwrap_or(0.0) as u64,
+    })
+}
@@ -0,0 +1,50 @@
+use super::{probe_audio_info, probe_native_info};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Prefix on errors for files that can't be read as audio, so the frontend
+/// can tell them apart from transcription failures.
+pub const AUDIO_PROBE_ERROR: &str = "AudioProbeError";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioInfo {
+    /// 0 when neither the header nor ffprobe reports it.
+    pub duration_ms: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Codec short name, e.g. `pcm_s16le` or `mp3`.
+    pub format: String,
+    pub size_bytes: u64,
+}
+
+/// Reads the header with the bundled decoders and falls back to `ffprobe`
+/// for anything they can't read or that records no duration. A file the
+/// bundled decoders can read is never rejected just because ffprobe is
+/// missing.
+pub async fn probe_audio(app: &AppHandle, path: &Path) -> Result<AudioInfo, String> {
+    let size_bytes = tokio::fs::metadata(path)
+        .await
+        .map(|m| m.len())
+        .map_err(|e| format!("{}: Failed to read audio file: {}", AUDIO_PROBE_ERROR, e))?;
+    if size_bytes == 0 {
+        return Err(format!("{}: Audio file is empty", AUDIO_PROBE_ERROR));
+    }
+
+    let native_path = path.to_path_buf();
+    let native = tokio::task::spawn_blocking(move || probe_native_info(&native_path))
+        .await
+        .ok()
+        .flatten();
+    let mut info = match native {
+        Some(info) if info.duration_ms > 0 => info,
+        native => match (probe_audio_info(app, path).await, native) {
+            (Ok(info), _) => info,
+            (Err(_), Some(info)) => info,
+            (Err(e), None) => return Err(format!("{}: {}", AUDIO_PROBE_ERROR, e)),
+        },
+    };
+
+    info.size_bytes = size_bytes;
+    Ok(info)
+}
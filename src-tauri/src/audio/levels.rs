@@ -0,0 +1,120 @@
+use super::open_audio;
+use serde::Serialize;
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+
+/// Below this average level whisper starts missing quiet speech.
+const QUIET_RMS_DBFS: f64 = -35.0;
+/// Samples this close to full scale are treated as clipped.
+const CLIP_THRESHOLD: f32 = 0.999;
+/// Fraction of clipped samples above which distortion becomes audible.
+const CLIP_RATIO_WARNING: f64 = 0.0001;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioLevels {
+    pub peak_dbfs: f64,
+    pub rms_dbfs: f64,
+    pub clipped_samples: u64,
+    pub total_samples: u64,
+    pub warnings: Vec<String>,
+}
+
+fn to_dbfs(amplitude: f64) -> f64 {
+    if amplitude > 0.0 {
+        20.0 * amplitude.log10()
+    } else {
+        f64::NEG_INFINITY
+    }
+}
+
+/// Decodes the whole file, so callers should run this on a blocking thread.
+pub fn analyze_levels(path: &Path) -> Result<AudioLevels, String> {
+    let mut format = open_audio(path)?;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| "No audio track found".to_string())?;
+    let track_id = track.id;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let mut peak: f32 = 0.0;
+    let mut sum_squares: f64 = 0.0;
+    let mut clipped_samples: u64 = 0;
+    let mut total_samples: u64 = 0;
+    let mut samples: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(format!("Failed to read audio: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame shouldn't abort analysis of the rest of the file.
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+
+        let buffer = samples
+            .get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, *decoded.spec()));
+        if buffer.capacity() < decoded.capacity() * decoded.spec().channels.count() {
+            *buffer = SampleBuffer::new(decoded.capacity() as u64, *decoded.spec());
+        }
+        buffer.copy_interleaved_ref(decoded);
+
+        for &sample in buffer.samples() {
+            let magnitude = sample.abs();
+            peak = peak.max(magnitude);
+            sum_squares += f64::from(sample) * f64::from(sample);
+            if magnitude >= CLIP_THRESHOLD {
+                clipped_samples += 1;
+            }
+        }
+        total_samples += buffer.samples().len() as u64;
+    }
+
+    if total_samples == 0 {
+        return Err("Audio file contains no samples".to_string());
+    }
+
+    let peak_dbfs = to_dbfs(f64::from(peak));
+    let rms_dbfs = to_dbfs((sum_squares / total_samples as f64).sqrt());
+
+    let mut warnings = Vec::new();
+    if peak == 0.0 {
+        warnings.push("Audio is completely silent".to_string());
+    } else if rms_dbfs < QUIET_RMS_DBFS {
+        warnings.push(format!(
+            "Audio is very quiet ({:.0} dBFS), consider normalizing",
+            rms_dbfs
+        ));
+    }
+    if clipped_samples as f64 / total_samples as f64 > CLIP_RATIO_WARNING {
+        warnings.push(format!(
+            "Clipping detected: {} samples at full scale",
+            clipped_samples
+        ));
+    }
+
+    Ok(AudioLevels {
+        peak_dbfs,
+        rms_dbfs,
+        clipped_samples,
+        total_samples,
+        warnings,
+    })
+}
@@ -0,0 +1,11 @@
+pub mod convert;
+pub mod ffprobe;
+pub mod info;
+pub mod levels;
+pub mod probe;
+
+pub use convert::*;
+pub use ffprobe::*;
+pub use info::*;
+pub use levels::*;
+pub use probe::*;
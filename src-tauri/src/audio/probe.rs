@@ -0,0 +1,53 @@
+use super::AudioInfo;
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Opens an audio file with the container guessed from its contents and
+/// extension.
+pub fn open_audio(path: &Path) -> Result<Box<dyn FormatReader>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Unsupported audio format: {}", e))?;
+    Ok(probed.format)
+}
+
+/// Stream details from the container header, without decoding. `None` when
+/// the format can't be read. `duration_ms` is 0 when the header doesn't
+/// record a frame count, as with some streamed MP3s, and `size_bytes` is
+/// left at 0 for the caller.
+pub fn probe_native_info(path: &Path) -> Option<AudioInfo> {
+    let format = open_audio(path).ok()?;
+    let params = &format.default_track()?.codec_params;
+    let sample_rate = params.sample_rate.filter(|&rate| rate > 0)?;
+
+    Some(AudioInfo {
+        duration_ms: params
+            .n_frames
+            .map(|frames| frames * 1000 / u64::from(sample_rate))
+            .unwrap_or(0),
+        sample_rate,
+        channels: params.channels.map(|c| c.count() as u16).unwrap_or(0),
+        format: symphonia::default::get_codecs()
+            .get_codec(params.codec)
+            .map(|codec| codec.short_name.to_string())
+            .unwrap_or_else(|| "unknown".to_string()),
+        size_bytes: 0,
+    })
+}
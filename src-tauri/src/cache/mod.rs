@@ -0,0 +1,3 @@
+pub mod transcriptions;
+
+pub use transcriptions::*;
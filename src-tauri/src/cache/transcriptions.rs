@@ -0,0 +1,84 @@
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+
+pub fn get_transcription_cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.whisper-gui.app")
+        .join("transcriptions")
+}
+
+fn fingerprint(hasher: &mut Sha256, path: &Path) -> Result<(), String> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read metadata for {}: {}", path.display(), e))?;
+
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    hasher.update(metadata.len().to_le_bytes());
+    hasher.update(modified.to_le_bytes());
+    Ok(())
+}
+
+/// Keys a cached result by the audio and model files' size and mtime plus
+/// the exact whisper-cli arguments. Hashing whole multi-gigabyte files on
+/// every run would cost more than the cache saves; the args already include
+/// both paths and every transcription option.
+pub fn transcription_cache_key(
+    audio_path: &Path,
+    model_path: &Path,
+    args: &[String],
+) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    fingerprint(&mut hasher, audio_path)?;
+    fingerprint(&mut hasher, model_path)?;
+    for arg in args {
+        hasher.update(arg.as_bytes());
+        hasher.update([0]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn get_cache_entry_path(key: &str) -> PathBuf {
+    get_transcription_cache_dir().join(format!("{}.txt", key))
+}
+
+pub async fn read_cached_transcription(key: &str) -> Option<String> {
+    fs::read_to_string(get_cache_entry_path(key)).await.ok()
+}
+
+pub async fn write_cached_transcription(key: &str, output: &str) -> Result<(), String> {
+    let cache_dir = get_transcription_cache_dir();
+    fs::create_dir_all(&cache_dir)
+        .await
+        .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+
+    let path = get_cache_entry_path(key);
+    let temp_path = path.with_extension("txt.tmp");
+    fs::write(&temp_path, output)
+        .await
+        .map_err(|e| format!("Failed to write cache entry: {}", e))?;
+
+    fs::rename(&temp_path, &path)
+        .await
+        .map_err(|e| format!("Failed to finalize cache entry: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn clear_transcription_cache_dir() -> Result<(), String> {
+    let cache_dir = get_transcription_cache_dir();
+    if cache_dir.exists() {
+        fs::remove_dir_all(&cache_dir)
+            .await
+            .map_err(|e| format!("Failed to clear transcription cache: {}", e))?;
+    }
+    Ok(())
+}
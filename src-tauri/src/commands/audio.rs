@@ -0,0 +1,29 @@
+use crate::audio::{
+    analyze_levels, probe_audio, probe_audio_info, AudioInfo, AudioLevels, AudioStreamInfo,
+};
+use crate::paths::resolve_input_file;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn analyze_audio_levels(path: String) -> Result<AudioLevels, String> {
+    let path = resolve_input_file(&path, "Audio file").await?;
+
+    tokio::task::spawn_blocking(move || analyze_levels(&path))
+        .await
+        .map_err(|e| format!("Audio analysis failed: {}", e))?
+}
+
+/// Superseded by `get_audio_info`; kept for callers that expect this shape.
+#[tauri::command]
+pub async fn audio_info(app: AppHandle, audio_path: String) -> Result<AudioStreamInfo, String> {
+    let path = resolve_input_file(&audio_path, "Audio file").await?;
+    probe_audio_info(&app, &path)
+        .await
+        .map(AudioStreamInfo::from)
+}
+
+#[tauri::command]
+pub async fn get_audio_info(app: AppHandle, path: String) -> Result<AudioInfo, String> {
+    let path = resolve_input_file(&path, "Audio file").await?;
+    probe_audio(&app, &path).await
+}
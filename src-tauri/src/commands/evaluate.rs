@@ -0,0 +1,124 @@
+use crate::downloader::get_model_path;
+use crate::evaluation::{character_error_rate, word_error_rate, ErrorRate};
+use crate::paths::resolve_input_file;
+use crate::whisper::{
+    collect_transcription, transcript_text, JobHandle, JobRegistry, WhisperOptions, WorkerPool,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvaluationSample {
+    pub audio: String,
+    pub reference_text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SampleEvaluation {
+    pub audio: String,
+    pub hypothesis: String,
+    pub wer: ErrorRate,
+    pub cer: ErrorRate,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelEvaluation {
+    pub model_name: String,
+    pub samples: Vec<SampleEvaluation>,
+    pub wer: ErrorRate,
+    pub cer: ErrorRate,
+    pub elapsed_ms: u64,
+}
+
+async fn transcribe_to_text(
+    app: AppHandle,
+    audio_path: &Path,
+    model_path: &Path,
+    language: Option<&str>,
+    job: &JobHandle,
+) -> Result<String, String> {
+    let options = WhisperOptions {
+        language: language.map(str::to_string),
+        ..Default::default()
+    };
+    collect_transcription(app, audio_path, model_path, &options, job)
+        .await
+        .map(|output| transcript_text(&output))
+}
+
+#[tauri::command]
+pub async fn evaluate_model(
+    app: AppHandle,
+    pool: State<'_, WorkerPool>,
+    jobs: State<'_, JobRegistry>,
+    model_name: String,
+    samples: Vec<EvaluationSample>,
+    language: Option<String>,
+) -> Result<ModelEvaluation, String> {
+    if samples.is_empty() {
+        return Err("No samples to evaluate".to_string());
+    }
+
+    let model_path = get_model_path(&model_name);
+    if !model_path.exists() {
+        return Err(format!("Model '{}' not downloaded", model_name));
+    }
+
+    let started = Instant::now();
+    let mut results = Vec::with_capacity(samples.len());
+
+    for sample in samples {
+        let sample_started = Instant::now();
+        let transcription = match resolve_input_file(&sample.audio, "Audio file").await {
+            Ok(audio_path) => {
+                let job = jobs.register(&model_path);
+                let _permit = pool.acquire().await;
+                transcribe_to_text(
+                    app.clone(),
+                    &audio_path,
+                    &model_path,
+                    language.as_deref(),
+                    &job,
+                )
+                .await
+            }
+            Err(err) => Err(err),
+        };
+
+        let result = match transcription {
+            Ok(hypothesis) => SampleEvaluation {
+                wer: word_error_rate(&sample.reference_text, &hypothesis),
+                cer: character_error_rate(&sample.reference_text, &hypothesis),
+                audio: sample.audio,
+                hypothesis,
+                elapsed_ms: sample_started.elapsed().as_millis() as u64,
+                error: None,
+            },
+            Err(err) => SampleEvaluation {
+                audio: sample.audio,
+                hypothesis: String::new(),
+                wer: ErrorRate::default(),
+                cer: ErrorRate::default(),
+                elapsed_ms: sample_started.elapsed().as_millis() as u64,
+                error: Some(err),
+            },
+        };
+        results.push(result);
+    }
+
+    let scored = results.iter().filter(|r| r.error.is_none());
+    let wer = ErrorRate::combine(scored.clone().map(|r| r.wer));
+    let cer = ErrorRate::combine(scored.map(|r| r.cer));
+
+    Ok(ModelEvaluation {
+        model_name,
+        samples: results,
+        wer,
+        cer,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,12 @@
+use crate::history::{HistoryEntry, HistoryStore};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_history(store: State<'_, HistoryStore>) -> Result<Vec<HistoryEntry>, String> {
+    Ok(store.entries().await)
+}
+
+#[tauri::command]
+pub async fn clear_history(store: State<'_, HistoryStore>) -> Result<(), String> {
+    store.clear().await
+}
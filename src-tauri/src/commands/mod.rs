@@ -1,5 +1,23 @@
+pub mod audio;
+pub mod evaluate;
+pub mod history;
 pub mod models;
+pub mod queue;
+pub mod settings;
+pub mod statistics;
+pub mod subtitles;
+pub mod system;
 pub mod transcribe;
+pub mod workers;
 
+pub use audio::*;
+pub use evaluate::*;
+pub use history::*;
 pub use models::*;
+pub use queue::*;
+pub use settings::*;
+pub use statistics::*;
+pub use subtitles::*;
+pub use system::*;
 pub use transcribe::*;
+pub use workers::*;
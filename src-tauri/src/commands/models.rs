@@ -1,10 +1,21 @@
 use crate::downloader::{
-    download_model, get_available_models, get_model_path, is_model_downloaded, ModelInfo,
+    build_client, check_ggml_magic, delete_custom_model, download_model, estimated_memory_mb,
+    get_available_models, get_model_path, get_temp_model_path, import_model_file, is_english_only,
+    is_model_downloaded, lock_model, lock_model_catalog, save_custom_model, sha256_file,
+    supports_range_requests, DownloadQueue, DownloadRegistry, ModelInfo,
 };
+use crate::paths::resolve_input_file;
+use crate::scheduler::{ResourceScheduler, WorkKind};
+use crate::settings::SettingsManager;
+use crate::statistics::StatisticsStore;
+use crate::whisper::{get_sidecar_path, JobRegistry};
 use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use std::time::UNIX_EPOCH;
+use sysinfo::System;
+use tauri::{AppHandle, Emitter, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelStatus {
@@ -20,6 +31,43 @@ pub struct DownloadProgress {
     pub percent: f64,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelReadiness {
+    pub model_name: String,
+    pub downloaded: bool,
+    pub file_valid: bool,
+    pub required_memory_mb: u64,
+    pub available_memory_mb: u64,
+    pub enough_memory: bool,
+    pub language_compatible: bool,
+    pub sidecar_available: bool,
+    pub ready: bool,
+    pub blocking_reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDetails {
+    pub info: ModelInfo,
+    pub downloaded: bool,
+    pub size_on_disk_bytes: Option<u64>,
+    pub downloaded_at_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelVerification {
+    pub model_name: String,
+    pub sha256: String,
+    pub expected_sha256: Option<String>,
+    /// `None` when there is no known hash to compare against.
+    pub verified: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadResumeInfo {
+    pub partial_bytes: Option<u64>,
+    pub resumable: bool,
+}
+
 #[tauri::command]
 pub async fn list_models() -> Result<Vec<ModelStatus>, String> {
     let models = get_available_models();
@@ -36,11 +84,78 @@ pub async fn list_models() -> Result<Vec<ModelStatus>, String> {
     Ok(result)
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadCancelled {
+    pub model_name: String,
+}
+
+/// `position` is 1-based among the downloads waiting for a slot.
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadQueued {
+    pub model_name: String,
+    pub position: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadDeferred {
+    pub model_name: String,
+    pub reason: String,
+}
+
+/// With `start_anyway`, the download starts even while a transcription is
+/// running.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn download_model_command(
     app: AppHandle,
+    scheduler: State<'_, ResourceScheduler>,
+    statistics: State<'_, StatisticsStore>,
+    settings: State<'_, SettingsManager>,
+    downloads: State<'_, DownloadRegistry>,
+    queue: State<'_, DownloadQueue>,
     model_name: String,
+    start_anyway: Option<bool>,
 ) -> Result<String, String> {
+    let settings = settings.get().await;
+    let mut handle = downloads.register(&model_name)?;
+
+    let notify_queued = |waiting: &[String]| {
+        for (index, name) in waiting.iter().enumerate() {
+            let _ = app.emit(
+                "download-queued",
+                DownloadQueued {
+                    model_name: name.clone(),
+                    position: index + 1,
+                },
+            );
+        }
+    };
+    let _slot = tokio::select! {
+        permit = queue.acquire(&model_name, notify_queued) => permit,
+        _ = handle.cancelled() => {
+            let message = format!("Download of '{}' was cancelled", model_name);
+            let _ = app.emit("download-cancelled", DownloadCancelled { model_name });
+            return Err(message);
+        }
+    };
+
+    let _resources = scheduler
+        .start(
+            WorkKind::Download,
+            settings.serialize_heavy_work,
+            start_anyway.unwrap_or(false),
+            || {
+                let _ = app.emit(
+                    "download-deferred",
+                    DownloadDeferred {
+                        model_name: model_name.clone(),
+                        reason: "Waiting for the running transcription to finish".to_string(),
+                    },
+                );
+            },
+        )
+        .await;
+
     let downloaded = Arc::new(AtomicU64::new(0));
     let total = Arc::new(AtomicU64::new(0));
     let model_name_clone = model_name.clone();
@@ -70,11 +185,105 @@ pub async fn download_model_command(
         );
     };
 
-    let path = download_model(&model_name, progress_callback).await?;
+    let path = match download_model(&model_name, &settings, &mut handle, progress_callback).await {
+        Ok(path) => path,
+        Err(err) => {
+            if handle.is_cancelled() {
+                let _ = app.emit("download-cancelled", DownloadCancelled { model_name });
+            }
+            return Err(err);
+        }
+    };
+
+    let bytes = tokio::fs::metadata(&path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    if let Err(err) = statistics.record_download(bytes).await {
+        eprintln!("{}", err);
+    }
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Copies or hard-links an existing ggml file into the models folder as
+/// `model_name`, which must be a known model that isn't downloaded yet. The
+/// file is checked for the ggml header, and against the published checksum
+/// when there is one. Progress is reported through `download-progress` like
+/// a download.
+#[tauri::command]
+pub async fn import_model(
+    app: AppHandle,
+    source_path: String,
+    model_name: String,
+) -> Result<String, String> {
+    let source = resolve_input_file(&source_path, "Model file").await?;
+
+    let path = import_model_file(&source, &model_name, |copied, total| {
+        let percent = if total > 0 {
+            (copied as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+        let _ = app.emit(
+            "download-progress",
+            DownloadProgress {
+                model_name: model_name.clone(),
+                downloaded: copied,
+                total,
+                percent,
+            },
+        );
+    })
+    .await?;
 
     Ok(path.to_string_lossy().to_string())
 }
 
+/// Succeeds even when the model isn't downloading, so a stale cancel button
+/// can't fail.
+#[tauri::command]
+pub async fn cancel_download(
+    downloads: State<'_, DownloadRegistry>,
+    model_name: String,
+) -> Result<(), String> {
+    downloads.cancel(&model_name);
+    Ok(())
+}
+
+/// `size_mb` is only used for the disk space check and the model list; leave
+/// it out when unknown. With `sha256`, downloads and imports of the model are
+/// checked against it.
+#[tauri::command]
+pub async fn add_custom_model(
+    name: String,
+    display_name: String,
+    url: String,
+    size_mb: Option<u64>,
+    sha256: Option<String>,
+) -> Result<ModelInfo, String> {
+    let model = ModelInfo {
+        name,
+        display_name,
+        size_mb: size_mb.unwrap_or(0),
+        description: "Custom model".to_string(),
+        url: url.trim().to_string(),
+        version: None,
+        release_notes: None,
+        sha256: sha256
+            .map(|sha256| sha256.trim().to_ascii_lowercase())
+            .filter(|sha256| !sha256.is_empty()),
+        quantization: None,
+    };
+    save_custom_model(model.clone()).await?;
+    Ok(model)
+}
+
+#[tauri::command]
+pub async fn remove_custom_model(name: String) -> Result<(), String> {
+    delete_custom_model(&name).await
+}
+
 #[tauri::command]
 pub async fn get_model_path_command(model_name: String) -> Result<String, String> {
     let path = get_model_path(&model_name);
@@ -86,12 +295,184 @@ pub async fn get_model_path_command(model_name: String) -> Result<String, String
 }
 
 #[tauri::command]
-pub async fn delete_model(model_name: String) -> Result<(), String> {
+pub async fn delete_model(jobs: State<'_, JobRegistry>, model_name: String) -> Result<(), String> {
     let path = get_model_path(&model_name);
-    if path.exists() {
-        tokio::fs::remove_file(&path)
-            .await
-            .map_err(|e| format!("Failed to delete model: {}", e))?;
+    if !path.exists() {
+        return Ok(());
     }
-    Ok(())
+    if jobs.is_model_in_use(&path) {
+        return Err(format!(
+            "Model '{}' is in use by a running transcription",
+            model_name
+        ));
+    }
+
+    let _lock = lock_model(&model_name)?;
+    let _catalog_lock = lock_model_catalog()?;
+
+    tokio::fs::remove_file(&path)
+        .await
+        .map_err(|e| delete_error_message(&model_name, &e))
+}
+
+fn delete_error_message(model_name: &str, err: &std::io::Error) -> String {
+    // Windows refuses to delete a file another process has open.
+    if cfg!(windows) && err.raw_os_error() == Some(32) {
+        return format!("Model '{}' is open in another program", model_name);
+    }
+
+    match err.kind() {
+        ErrorKind::PermissionDenied => format!(
+            "Permission denied deleting model '{}'; it may be open in another program",
+            model_name
+        ),
+        ErrorKind::NotFound => format!("Model '{}' was already removed", model_name),
+        ErrorKind::ResourceBusy => format!("Model '{}' is busy", model_name),
+        ErrorKind::ReadOnlyFilesystem => format!(
+            "Can't delete model '{}': the models folder is on a read-only filesystem",
+            model_name
+        ),
+        _ => format!("Failed to delete model: {}", err),
+    }
+}
+
+#[tauri::command]
+pub async fn get_model_details(model_name: String) -> Result<ModelDetails, String> {
+    let info = get_available_models()
+        .into_iter()
+        .find(|m| m.name == model_name)
+        .ok_or_else(|| format!("Model '{}' not found", model_name))?;
+
+    let metadata = tokio::fs::metadata(get_model_path(&model_name)).await.ok();
+    let downloaded_at_ms = metadata
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64);
+
+    Ok(ModelDetails {
+        info,
+        downloaded: metadata.is_some(),
+        size_on_disk_bytes: metadata.as_ref().map(|m| m.len()),
+        downloaded_at_ms,
+    })
+}
+
+#[tauri::command]
+pub async fn verify_model(model_name: String) -> Result<ModelVerification, String> {
+    let info = get_available_models()
+        .into_iter()
+        .find(|m| m.name == model_name)
+        .ok_or_else(|| format!("Model '{}' not found", model_name))?;
+
+    let path = get_model_path(&model_name);
+    if !path.exists() {
+        return Err(format!("Model '{}' not downloaded", model_name));
+    }
+
+    let sha256 = sha256_file(&path).await?;
+    let verified = info
+        .sha256
+        .as_ref()
+        .map(|expected| expected.eq_ignore_ascii_case(&sha256));
+
+    Ok(ModelVerification {
+        model_name,
+        sha256,
+        expected_sha256: info.sha256,
+        verified,
+    })
+}
+
+#[tauri::command]
+pub async fn get_model_readiness(
+    model_name: String,
+    target_language: Option<String>,
+) -> Result<ModelReadiness, String> {
+    let model = get_available_models()
+        .into_iter()
+        .find(|m| m.name == model_name)
+        .ok_or_else(|| format!("Model '{}' not found", model_name))?;
+
+    let mut blocking_reasons = Vec::new();
+
+    let path = get_model_path(&model_name);
+    let downloaded = path.exists();
+    let file_valid = downloaded && check_ggml_magic(&path).await.is_ok();
+    if !downloaded {
+        blocking_reasons.push(format!("Model '{}' is not downloaded", model_name));
+    } else if !file_valid {
+        blocking_reasons.push(format!(
+            "Model file for '{}' is damaged; delete and download it again",
+            model_name
+        ));
+    }
+
+    let mut system = System::new();
+    system.refresh_memory();
+    let available_memory_mb = system.available_memory() / (1024 * 1024);
+    let required_memory_mb = estimated_memory_mb(model.size_mb);
+    let enough_memory = available_memory_mb >= required_memory_mb;
+    if !enough_memory {
+        blocking_reasons.push(format!(
+            "Not enough free memory: need about {}MB, have {}MB",
+            required_memory_mb, available_memory_mb
+        ));
+    }
+
+    let language_compatible = match target_language.as_deref() {
+        Some(lang) if is_english_only(&model_name) => lang == "en" || lang == "auto",
+        _ => true,
+    };
+    if !language_compatible {
+        blocking_reasons.push(format!("Model '{}' only supports English", model_name));
+    }
+
+    let sidecar_available = get_sidecar_path().is_some_and(|p| p.exists());
+    if !sidecar_available {
+        blocking_reasons.push("The whisper-cli sidecar binary is missing".to_string());
+    }
+
+    Ok(ModelReadiness {
+        model_name,
+        downloaded,
+        file_valid,
+        required_memory_mb,
+        available_memory_mb,
+        enough_memory,
+        language_compatible,
+        sidecar_available,
+        ready: blocking_reasons.is_empty(),
+        blocking_reasons,
+    })
+}
+
+#[tauri::command]
+pub async fn get_download_resume_info(
+    settings: State<'_, SettingsManager>,
+    model_name: String,
+) -> Result<DownloadResumeInfo, String> {
+    let model = get_available_models()
+        .into_iter()
+        .find(|m| m.name == model_name)
+        .ok_or_else(|| format!("Model '{}' not found", model_name))?;
+
+    let partial_bytes = tokio::fs::metadata(get_temp_model_path(&model_name))
+        .await
+        .ok()
+        .map(|m| m.len())
+        .filter(|&len| len > 0);
+
+    let resumable = match partial_bytes {
+        Some(_) => {
+            let client = build_client(&settings.get().await)?;
+            supports_range_requests(&client, &model.url).await?
+        }
+        None => false,
+    };
+
+    Ok(DownloadResumeInfo {
+        partial_bytes,
+        resumable,
+    })
 }
@@ -0,0 +1,281 @@
+use super::{start_transcription, TranscriptionRequest};
+use crate::downloader::get_model_path;
+use crate::paths::resolve_input_file;
+use crate::queue::{BatchPosition, QueueItem, QueueStatus, TranscriptionQueue};
+use crate::whisper::OutputFormats;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueUpdated {
+    pub items: Vec<QueueItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchProgress {
+    pub batch_id: String,
+    /// 0-based.
+    pub current_index: usize,
+    pub total: usize,
+    pub current_file: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchFailure {
+    pub audio_path: String,
+    pub error: String,
+}
+
+/// Files removed from the queue before they ran are in neither list.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchComplete {
+    pub batch_id: String,
+    pub succeeded: Vec<String>,
+    pub failed: Vec<BatchFailure>,
+}
+
+/// Seeds a batch's summary with the files that failed before running.
+fn batch_summary(batch_id: &str, items: &[QueueItem]) -> BatchComplete {
+    let failed = items
+        .iter()
+        .filter(|item| {
+            item.status == QueueStatus::Failed
+                && item
+                    .batch
+                    .as_ref()
+                    .is_some_and(|batch| batch.id == batch_id)
+        })
+        .map(|item| BatchFailure {
+            audio_path: item.audio_path.clone(),
+            error: item.error.clone().unwrap_or_default(),
+        })
+        .collect();
+    BatchComplete {
+        batch_id: batch_id.to_string(),
+        succeeded: Vec::new(),
+        failed,
+    }
+}
+
+fn emit_queue(app: &AppHandle) {
+    let items = app.state::<TranscriptionQueue>().items();
+    let _ = app.emit("queue-updated", QueueUpdated { items });
+}
+
+/// Files are transcribed one at a time, in order. A failed file is marked
+/// and the queue moves on; so is a path that can't be read, which is queued
+/// as already failed.
+#[tauri::command]
+pub async fn enqueue_transcriptions(
+    app: AppHandle,
+    queue: State<'_, TranscriptionQueue>,
+    paths: Vec<String>,
+    model_name: String,
+    output_format: OutputFormats,
+    language: Option<String>,
+) -> Result<Vec<QueueItem>, String> {
+    enqueue(
+        &app,
+        &queue,
+        paths,
+        model_name,
+        output_format,
+        language,
+        false,
+    )
+    .await
+}
+
+/// Queues the files as one batch. Besides the usual queue events, each file
+/// is announced with `batch-progress` and `batch-complete` follows the last.
+#[tauri::command]
+pub async fn transcribe_batch(
+    app: AppHandle,
+    queue: State<'_, TranscriptionQueue>,
+    audio_paths: Vec<String>,
+    model_name: String,
+    output_format: OutputFormats,
+    language: Option<String>,
+) -> Result<Vec<QueueItem>, String> {
+    enqueue(
+        &app,
+        &queue,
+        audio_paths,
+        model_name,
+        output_format,
+        language,
+        true,
+    )
+    .await
+}
+
+async fn enqueue(
+    app: &AppHandle,
+    queue: &TranscriptionQueue,
+    paths: Vec<String>,
+    model_name: String,
+    output_format: OutputFormats,
+    language: Option<String>,
+    batch: bool,
+) -> Result<Vec<QueueItem>, String> {
+    if paths.is_empty() {
+        return Err("No files to transcribe".to_string());
+    }
+    let output_formats = output_format.parse()?;
+    if !get_model_path(&model_name).exists() {
+        return Err(format!("Model '{}' not downloaded", model_name));
+    }
+
+    let batch_id = batch.then(|| uuid::Uuid::new_v4().to_string());
+    let total = paths.len();
+    let mut items = Vec::with_capacity(total);
+    for (index, path) in paths.into_iter().enumerate() {
+        let (audio_path, status, error) = match resolve_input_file(&path, "Audio file").await {
+            Ok(audio_path) => (
+                audio_path.to_string_lossy().to_string(),
+                QueueStatus::Pending,
+                None,
+            ),
+            Err(err) => (path, QueueStatus::Failed, Some(err)),
+        };
+        items.push(QueueItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            audio_path,
+            model_name: model_name.clone(),
+            output_formats: output_formats.clone(),
+            language: language.clone(),
+            status,
+            job_id: None,
+            output_files: Vec::new(),
+            error,
+            batch: batch_id.as_ref().map(|id| BatchPosition {
+                id: id.clone(),
+                index,
+                total,
+            }),
+        });
+    }
+
+    let runnable = items.iter().any(|item| item.status == QueueStatus::Pending);
+    if queue.push(items.clone()) {
+        tokio::spawn(run_queue(app.clone()));
+    }
+    emit_queue(app);
+    // Nothing will run, so the worker never reports this batch.
+    if let (Some(batch_id), false) = (batch_id, runnable) {
+        let _ = app.emit("batch-complete", batch_summary(&batch_id, &items));
+    }
+    Ok(items)
+}
+
+async fn run_queue(app: AppHandle) {
+    let mut batches: HashMap<String, BatchComplete> = HashMap::new();
+
+    while let Some(item) = app.state::<TranscriptionQueue>().start_next() {
+        emit_queue(&app);
+        if let Some(batch) = &item.batch {
+            let _ = app.emit(
+                "batch-progress",
+                BatchProgress {
+                    batch_id: batch.id.clone(),
+                    current_index: batch.index,
+                    total: batch.total,
+                    current_file: item.audio_path.clone(),
+                },
+            );
+        }
+
+        let result = transcribe_item(&app, &item).await;
+        if let Some(batch) = &item.batch {
+            let summary = batches.entry(batch.id.clone()).or_insert_with(|| {
+                batch_summary(&batch.id, &app.state::<TranscriptionQueue>().items())
+            });
+            match &result {
+                Ok(_) => summary.succeeded.push(item.audio_path.clone()),
+                Err(err) => {
+                    eprintln!("Failed to transcribe {}: {}", item.audio_path, err);
+                    summary.failed.push(BatchFailure {
+                        audio_path: item.audio_path.clone(),
+                        error: err.clone(),
+                    });
+                }
+            }
+        }
+        app.state::<TranscriptionQueue>().finish(&item.id, result);
+        emit_queue(&app);
+
+        let queue = app.state::<TranscriptionQueue>();
+        let finished: Vec<String> = batches
+            .keys()
+            .filter(|id| !queue.has_pending_in_batch(id))
+            .cloned()
+            .collect();
+        for id in finished {
+            if let Some(summary) = batches.remove(&id) {
+                let _ = app.emit("batch-complete", summary);
+            }
+        }
+    }
+
+    // Batches whose remaining files were removed from the queue.
+    for summary in batches.into_values() {
+        let _ = app.emit("batch-complete", summary);
+    }
+}
+
+/// Runs the item through the same pipeline as `transcribe_audio`, so it
+/// gets the same events, cache, statistics and history.
+async fn transcribe_item(app: &AppHandle, item: &QueueItem) -> Result<Vec<String>, String> {
+    let request = TranscriptionRequest {
+        audio_path: item.audio_path.clone(),
+        model_name: Some(item.model_name.clone()),
+        output_formats: item.output_formats.clone(),
+        language: item.language.clone(),
+        ..Default::default()
+    };
+    let done = start_transcription(app, request, |job_id| {
+        app.state::<TranscriptionQueue>()
+            .set_job_id(&item.id, job_id);
+        emit_queue(app);
+    })
+    .await?;
+
+    let complete = done
+        .await
+        .map_err(|_| "Transcription ended without a result".to_string())?;
+    if complete.success {
+        Ok(complete.output_files)
+    } else {
+        Err(complete
+            .error
+            .unwrap_or_else(|| "Transcription failed".to_string()))
+    }
+}
+
+#[tauri::command]
+pub async fn get_queue(queue: State<'_, TranscriptionQueue>) -> Result<Vec<QueueItem>, String> {
+    Ok(queue.items())
+}
+
+#[tauri::command]
+pub async fn remove_from_queue(
+    app: AppHandle,
+    queue: State<'_, TranscriptionQueue>,
+    id: String,
+) -> Result<(), String> {
+    queue.remove(&id)?;
+    emit_queue(&app);
+    Ok(())
+}
+
+/// Running items are left alone; cancel them with `cancel_transcription`.
+#[tauri::command]
+pub async fn clear_queue(
+    app: AppHandle,
+    queue: State<'_, TranscriptionQueue>,
+) -> Result<(), String> {
+    queue.clear();
+    emit_queue(&app);
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use super::validate_cpu_option;
+use crate::downloader::{DownloadQueue, INVALID_CERTS_WARNING, MAX_DOWNLOAD_CONCURRENCY};
+use crate::paths::is_user_picked;
+use crate::settings::{DownloadTuning, Settings, SettingsManager};
+use crate::subtitles::TranscriptFormat;
+use crate::summary::SummaryCommand;
+use crate::whisper::{max_worker_count, WorkerPool};
+use serde_json::Value;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+
+#[tauri::command]
+pub async fn get_settings(settings: State<'_, SettingsManager>) -> Result<Settings, String> {
+    Ok(settings.get().await)
+}
+
+/// Applies only the keys present in `patch`, on top of the current settings
+/// rather than whatever the caller last read. Emits `invalid-certs-warning`
+/// when certificate validation gets turned off.
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    pool: State<'_, WorkerPool>,
+    downloads: State<'_, DownloadQueue>,
+    patch: Value,
+) -> Result<Settings, String> {
+    let mut certs_disabled = false;
+    let updated = settings
+        .patch(&app, patch, |before, after| {
+            let mut resize_to = None;
+            let mut download_limit = None;
+            if after.download_tuning != before.download_tuning {
+                validate_download_tuning(&after.download_tuning)?;
+            }
+            if after.summary_command != before.summary_command {
+                if let Some(command) = &after.summary_command {
+                    command.validate()?;
+                }
+            }
+            if after.worker_count != before.worker_count {
+                if let Some(count) = after.worker_count {
+                    validate_worker_count(count)?;
+                    resize_to = Some(count);
+                }
+            }
+            if after.max_concurrent_downloads != before.max_concurrent_downloads {
+                if let Some(limit) = after.max_concurrent_downloads {
+                    validate_download_concurrency(limit)?;
+                    download_limit = Some(limit);
+                }
+            }
+            if after.default_format != before.default_format
+                && TranscriptFormat::parse(&after.default_format).is_none()
+            {
+                return Err(format!(
+                    "Unsupported output format '{}'; expected txt, srt, vtt or json",
+                    after.default_format
+                ));
+            }
+            if after.threads != before.threads {
+                validate_cpu_option(after.threads, "Thread count")?;
+            }
+            if after.output_dir != before.output_dir {
+                if let Some(dir) = &after.output_dir {
+                    let path = Path::new(dir);
+                    if !path.is_absolute() || !path.is_dir() {
+                        return Err(format!("Output folder not found: {}", dir));
+                    }
+                    // Every later write inside it is trusted, so it has to
+                    // come from the folder picker rather than the webview.
+                    if !is_user_picked(&app, path) {
+                        return Err(format!(
+                            "Choose the output folder with the folder picker: {}",
+                            dir
+                        ));
+                    }
+                }
+            }
+            certs_disabled =
+                after.danger_accept_invalid_certs && !before.danger_accept_invalid_certs;
+
+            // Resized under the settings lock, once nothing can fail, so
+            // concurrent updates can't leave the live limits and the saved
+            // ones apart.
+            if let Some(count) = resize_to {
+                pool.resize(count);
+            }
+            if let Some(limit) = download_limit {
+                downloads.resize(limit);
+            }
+            Ok(())
+        })
+        .await?;
+
+    if certs_disabled {
+        log_certs_disabled();
+        let _ = app.emit("invalid-certs-warning", INVALID_CERTS_WARNING);
+    }
+    Ok(updated)
+}
+
+/// Replaces all settings at once; validated the same way as `update_settings`.
+#[tauri::command]
+pub async fn save_settings(
+    app: AppHandle,
+    manager: State<'_, SettingsManager>,
+    pool: State<'_, WorkerPool>,
+    downloads: State<'_, DownloadQueue>,
+    settings: Settings,
+) -> Result<Settings, String> {
+    let patch = serde_json::to_value(&settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    update_settings(app, manager, pool, downloads, patch).await
+}
+
+/// Logged once when validation is turned off, not on every download.
+fn log_certs_disabled() {
+    eprintln!("WARNING: {}", INVALID_CERTS_WARNING);
+}
+
+pub fn validate_worker_count(count: usize) -> Result<(), String> {
+    let max = max_worker_count();
+    if count == 0 || count > max {
+        return Err(format!("Worker count must be between 1 and {}", max));
+    }
+    Ok(())
+}
+
+fn validate_download_concurrency(limit: usize) -> Result<(), String> {
+    if limit == 0 || limit > MAX_DOWNLOAD_CONCURRENCY {
+        return Err(format!(
+            "Concurrent downloads must be between 1 and {}",
+            MAX_DOWNLOAD_CONCURRENCY
+        ));
+    }
+    Ok(())
+}
+
+fn validate_download_tuning(tuning: &DownloadTuning) -> Result<(), String> {
+    if tuning.tcp_keepalive_secs == Some(0) || tuning.pool_idle_timeout_secs == Some(0) {
+        return Err("Timeouts must be at least one second".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_accept_invalid_certs(
+    settings: State<'_, SettingsManager>,
+) -> Result<bool, String> {
+    Ok(settings.get().await.danger_accept_invalid_certs)
+}
+
+/// Returns the security warning to show the user when validation is turned off.
+#[tauri::command]
+pub async fn set_accept_invalid_certs(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> Result<Option<String>, String> {
+    let mut certs_disabled = false;
+    settings
+        .update(&app, |s| {
+            certs_disabled = enabled && !s.danger_accept_invalid_certs;
+            s.danger_accept_invalid_certs = enabled;
+            Ok(())
+        })
+        .await?;
+
+    if certs_disabled {
+        log_certs_disabled();
+    }
+    if enabled {
+        Ok(Some(INVALID_CERTS_WARNING.to_string()))
+    } else {
+        Ok(None)
+    }
+}
+
+#[tauri::command]
+pub async fn get_keyword_notifications(
+    settings: State<'_, SettingsManager>,
+) -> Result<bool, String> {
+    Ok(settings.get().await.keyword_notifications)
+}
+
+#[tauri::command]
+pub async fn set_keyword_notifications(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings
+        .update(&app, |s| {
+            s.keyword_notifications = enabled;
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_offline_mode(settings: State<'_, SettingsManager>) -> Result<bool, String> {
+    Ok(settings.get().await.offline_mode)
+}
+
+#[tauri::command]
+pub async fn set_offline_mode(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings
+        .update(&app, |s| {
+            s.offline_mode = enabled;
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_unbatched_output_events(
+    settings: State<'_, SettingsManager>,
+) -> Result<bool, String> {
+    Ok(settings.get().await.unbatched_output_events)
+}
+
+#[tauri::command]
+pub async fn set_unbatched_output_events(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings
+        .update(&app, |s| {
+            s.unbatched_output_events = enabled;
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_download_tuning(
+    settings: State<'_, SettingsManager>,
+) -> Result<DownloadTuning, String> {
+    Ok(settings.get().await.download_tuning)
+}
+
+#[tauri::command]
+pub async fn set_download_tuning(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    tuning: DownloadTuning,
+) -> Result<(), String> {
+    validate_download_tuning(&tuning)?;
+
+    settings
+        .update(&app, |s| {
+            s.download_tuning = tuning;
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_summary_command(
+    settings: State<'_, SettingsManager>,
+) -> Result<Option<SummaryCommand>, String> {
+    Ok(settings.get().await.summary_command)
+}
+
+#[tauri::command]
+pub async fn set_summary_command(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    command: Option<SummaryCommand>,
+) -> Result<(), String> {
+    if let Some(command) = &command {
+        command.validate()?;
+    }
+
+    settings
+        .update(&app, |s| {
+            s.summary_command = command;
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_serialize_heavy_work(
+    settings: State<'_, SettingsManager>,
+) -> Result<bool, String> {
+    Ok(settings.get().await.serialize_heavy_work)
+}
+
+#[tauri::command]
+pub async fn set_serialize_heavy_work(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings
+        .update(&app, |s| {
+            s.serialize_heavy_work = enabled;
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_advanced_mode(settings: State<'_, SettingsManager>) -> Result<bool, String> {
+    Ok(settings.get().await.advanced_mode)
+}
+
+#[tauri::command]
+pub async fn set_advanced_mode(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> Result<(), String> {
+    settings
+        .update(&app, |s| {
+            s.advanced_mode = enabled;
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
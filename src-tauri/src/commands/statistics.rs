@@ -0,0 +1,28 @@
+use crate::statistics::{StatisticsStore, StatisticsSummary};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_statistics(
+    store: State<'_, StatisticsStore>,
+    period: Option<String>,
+) -> Result<StatisticsSummary, String> {
+    let period = period.unwrap_or_else(|| "all".to_string());
+    let days = match period.as_str() {
+        "week" => Some(7),
+        "month" => Some(30),
+        "all" => None,
+        other => {
+            return Err(format!(
+                "Unknown period '{}' (expected week, month or all)",
+                other
+            ))
+        }
+    };
+
+    Ok(store.summary(&period, days).await)
+}
+
+#[tauri::command]
+pub async fn reset_statistics(store: State<'_, StatisticsStore>) -> Result<(), String> {
+    store.reset().await
+}
@@ -0,0 +1,149 @@
+use crate::paths::{resolve_input_file, resolve_output_file};
+use crate::subtitles::{
+    clip_cues, format_transcript, format_txt, parse_transcript, preview_cues, rescale_timings,
+    CuePreview, SubtitleStyle, TranscriptFormat,
+};
+use std::path::Path;
+use tauri::AppHandle;
+use tokio::fs;
+
+fn is_subtitle_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("srt") || e.eq_ignore_ascii_case("vtt"))
+}
+
+#[tauri::command]
+pub async fn rescale_subtitle_timing(
+    app: AppHandle,
+    path: String,
+    source_fps: f64,
+    target_fps: f64,
+    output_path: String,
+) -> Result<String, String> {
+    if !(source_fps.is_finite() && source_fps > 0.0 && target_fps.is_finite() && target_fps > 0.0) {
+        return Err("Frame rates must be positive numbers".to_string());
+    }
+
+    let path = resolve_input_file(&path, "Subtitle file").await?;
+    let output = resolve_output_file(&app, &output_path).await?;
+    if !is_subtitle_file(&path) {
+        return Err(format!(
+            "Unsupported subtitle format: {} (expected .srt or .vtt)",
+            path.display()
+        ));
+    }
+
+    let contents = fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read subtitle file: {}", e))?;
+
+    let rescaled = rescale_timings(&contents, source_fps, target_fps)?;
+
+    fs::write(&output, rescaled)
+        .await
+        .map_err(|e| format!("Failed to write subtitle file: {}", e))?;
+
+    Ok(output.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn clip_transcript(
+    app: AppHandle,
+    json_or_srt_path: String,
+    start_ms: u64,
+    end_ms: u64,
+    rebase: bool,
+    output_path: String,
+) -> Result<String, String> {
+    if start_ms >= end_ms {
+        return Err("Clip start must be before clip end".to_string());
+    }
+
+    let path = resolve_input_file(&json_or_srt_path, "Transcript file").await?;
+    let output = resolve_output_file(&app, &output_path).await?;
+    let input_format = TranscriptFormat::from_path(&path).ok_or_else(|| {
+        format!(
+            "Unsupported transcript format: {} (expected .srt, .vtt or .json)",
+            path.display()
+        )
+    })?;
+    let output_format = TranscriptFormat::from_path(&output).unwrap_or(input_format);
+
+    let contents = fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read transcript file: {}", e))?;
+
+    let cues = parse_transcript(&contents, input_format)?;
+    let clipped = clip_cues(&cues, start_ms, end_ms, rebase);
+
+    fs::write(&output, format_transcript(&clipped, output_format))
+        .await
+        .map_err(|e| format!("Failed to write transcript file: {}", e))?;
+
+    Ok(output.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn render_subtitle_preview(
+    json_path: String,
+    style: Option<SubtitleStyle>,
+) -> Result<Vec<CuePreview>, String> {
+    let path = resolve_input_file(&json_path, "Transcript file").await?;
+    let format = TranscriptFormat::from_path(&path).ok_or_else(|| {
+        format!(
+            "Unsupported transcript format: {} (expected .json, .srt or .vtt)",
+            path.display()
+        )
+    })?;
+
+    let contents = fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read transcript file: {}", e))?;
+
+    let cues = parse_transcript(&contents, format)?;
+    Ok(preview_cues(&cues, &style.unwrap_or_default()))
+}
+
+#[tauri::command]
+pub async fn convert_transcript_file(
+    app: AppHandle,
+    input_path: String,
+    output_format: String,
+    output_path: String,
+    paragraph_gap_ms: Option<u64>,
+) -> Result<String, String> {
+    let path = resolve_input_file(&input_path, "Transcript file").await?;
+    let output = resolve_output_file(&app, &output_path).await?;
+
+    let input_format = TranscriptFormat::from_path(&path).ok_or_else(|| {
+        format!(
+            "Unsupported transcript format: {} (expected .srt, .vtt or .json)",
+            path.display()
+        )
+    })?;
+    let output_format = TranscriptFormat::parse(&output_format).ok_or_else(|| {
+        format!(
+            "Unsupported output format: {} (expected srt, vtt, json or txt)",
+            output_format
+        )
+    })?;
+
+    let contents = fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read transcript file: {}", e))?;
+
+    let cues = parse_transcript(&contents, input_format)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let converted = match (output_format, paragraph_gap_ms) {
+        (TranscriptFormat::Txt, Some(gap_ms)) => format_txt(&cues, gap_ms),
+        _ => format_transcript(&cues, output_format),
+    };
+
+    fs::write(&output, converted)
+        .await
+        .map_err(|e| format!("Failed to write transcript file: {}", e))?;
+
+    Ok(output.to_string_lossy().to_string())
+}
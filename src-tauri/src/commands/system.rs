@@ -0,0 +1,98 @@
+use crate::diagnostics::{
+    collect_app_info, collect_model_diagnostics, summarize_history, summarize_job,
+    write_diagnostics_zip, AppInfo, HistorySummary, JobSummary,
+};
+use crate::history::HistoryStore;
+use crate::paths::resolve_output_file;
+use crate::settings::SettingsManager;
+use crate::whisper::{
+    kill_orphaned_sidecars, max_worker_count, FlagInfo, JobRegistry, SidecarFlags, WorkerPool,
+};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    /// Upper bound for the `threads` and `processors` transcription options.
+    pub logical_cores: usize,
+    /// What whisper-cli uses when `threads` isn't given.
+    pub default_threads: usize,
+}
+
+#[tauri::command]
+pub async fn get_system_info() -> Result<SystemInfo, String> {
+    let logical_cores = max_worker_count();
+    Ok(SystemInfo {
+        logical_cores,
+        default_threads: logical_cores.min(4),
+    })
+}
+
+#[tauri::command]
+pub async fn kill_orphaned_processes() -> Result<Vec<u32>, String> {
+    tokio::task::spawn_blocking(kill_orphaned_sidecars)
+        .await
+        .map_err(|e| format!("Failed to scan processes: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_supported_flags(
+    app: AppHandle,
+    flags: State<'_, SidecarFlags>,
+) -> Result<Vec<FlagInfo>, String> {
+    Ok(flags.get(&app).await)
+}
+
+#[tauri::command]
+pub async fn get_app_info(app: AppHandle, pool: State<'_, WorkerPool>) -> Result<AppInfo, String> {
+    let version = app.package_info().version.to_string();
+    let workers = pool.count();
+    tokio::task::spawn_blocking(move || collect_app_info(version, workers))
+        .await
+        .map_err(|e| format!("Failed to collect system info: {}", e))
+}
+
+#[tauri::command]
+pub async fn generate_diagnostics(
+    app: AppHandle,
+    pool: State<'_, WorkerPool>,
+    flags: State<'_, SidecarFlags>,
+    settings: State<'_, SettingsManager>,
+    jobs: State<'_, JobRegistry>,
+    history: State<'_, HistoryStore>,
+    path: String,
+) -> Result<String, String> {
+    let path = resolve_output_file(&app, &path).await?;
+    let app_info = get_app_info(app.clone(), pool).await?;
+    let flags = flags.get(&app).await;
+    let models = collect_model_diagnostics().await;
+    let settings = settings.get().await;
+    let job_summaries: Vec<JobSummary> = jobs
+        .records()
+        .iter()
+        .map(|(record, running)| summarize_job(record, *running))
+        .collect();
+    let history: Vec<HistorySummary> = history
+        .entries()
+        .await
+        .iter()
+        .map(summarize_history)
+        .collect();
+
+    let output = path.clone();
+    tokio::task::spawn_blocking(move || {
+        write_diagnostics_zip(
+            &output,
+            &app_info,
+            &flags,
+            &models,
+            &settings,
+            &job_summaries,
+            &history,
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to write diagnostics: {}", e))??;
+
+    Ok(path.to_string_lossy().to_string())
+}
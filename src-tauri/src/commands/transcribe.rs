@@ -1,94 +1,1110 @@
-use crate::downloader::get_model_path;
-use crate::whisper::{run_transcription, TranscriptionEvent};
+use crate::audio::{prepare_for_whisper, probe_audio};
+use crate::cache::{
+    clear_transcription_cache_dir, read_cached_transcription, transcription_cache_key,
+    write_cached_transcription,
+};
+use crate::downloader::{estimated_memory_mb, get_model_path, is_english_only, resolve_model_file};
+use crate::history::{HistoryEntry, HistoryJob, HistoryStore};
+use crate::paths::{
+    resolve_input_dir, resolve_input_file, resolve_output_beside, resolve_output_dir,
+};
+use crate::scheduler::{ResourceScheduler, WorkKind};
+use crate::settings::SettingsManager;
+use crate::statistics::StatisticsStore;
+use crate::subtitles::{format_txt, Cue, TranscriptFormat, DEFAULT_PARAGRAPH_GAP_MS};
+use crate::summary::{run_summary_command, summary_path, SummaryCommand};
+use crate::whisper::{
+    available_memory_mb, build_args, classify_stderr, detect_language_with_sidecar, enforce_strict,
+    existing_output_files, free_space_mb, get_sidecar_path, max_worker_count, output_base,
+    output_file_path, parse_segments, parse_word_timings, run_preflight, run_transcription,
+    transcript_text, unknown_extra_flags, validate_extra_args, write_grammar_file,
+    DetectedLanguage, GrammarOptions, JobRecord, JobRegistry, KeywordMatcher, OutputFormats,
+    PreflightContext, ProcessPriority, Severity, SidecarFlags, TranscriptSegment,
+    TranscriptionEvent, WhisperOptions, WordTiming, WorkerPool,
+};
 use serde::Serialize;
-use std::path::PathBuf;
-use tauri::{AppHandle, Emitter};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::oneshot;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct TranscriptionOutput {
     pub line: String,
+    /// Kept for older listeners; true only for `Severity::Error` lines.
     pub is_error: bool,
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionOutputBatch {
+    pub lines: Vec<TranscriptionOutput>,
+    /// Segments parsed since the last batch; their lines are in `lines` too.
+    pub segments: Vec<TranscriptSegment>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct TranscriptionComplete {
     pub success: bool,
     pub output: String,
+    pub segments: Vec<TranscriptSegment>,
+    /// Files whisper-cli wrote for the requested formats.
+    pub output_files: Vec<String>,
+    /// Empty unless `word_timestamps` was requested.
+    pub words: Vec<WordTiming>,
+    /// Set when whisper-cli detected the language itself.
+    pub detected_language: Option<DetectedLanguage>,
+    pub error: Option<String>,
+    /// The user stopped the job; `error` then just says so.
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionProgress {
+    pub job_id: String,
+    pub percent: f64,
+    pub processed_ms: u64,
+    pub total_ms: u64,
+    /// Wall-clock time since whisper-cli started.
+    pub elapsed_ms: u64,
+    /// Extrapolated from the rate so far.
+    pub eta_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeywordDetected {
+    pub keyword: String,
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryComplete {
+    pub audio_path: String,
+    pub success: bool,
+    pub summary_path: Option<String>,
+    pub summary: Option<String>,
     pub error: Option<String>,
 }
 
+/// whisper-cli flags the installed sidecar doesn't list in `--help`. The
+/// run still goes ahead, since help output isn't always complete.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtraArgsWarning {
+    pub unknown_flags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheHit {
+    pub audio_path: String,
+}
+
+/// Sent as soon as a detection job exists, so it can be cancelled while it
+/// waits for a worker slot or runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageDetectionStarted {
+    pub job_id: String,
+    pub audio_path: String,
+}
+
+/// A fast GPU run emits dozens of lines per second; one IPC message per line
+/// makes the webview stutter, so lines are flushed in batches instead.
+const OUTPUT_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+const OUTPUT_BATCH_SIZE: usize = 50;
+
+/// Where `OutputSink` sends its events.
+trait OutputEmitter {
+    fn emit_line(&self, output: TranscriptionOutput);
+    fn emit_segment(&self, segment: TranscriptSegment);
+    fn emit_batch(&self, batch: TranscriptionOutputBatch);
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S);
+}
+
+impl OutputEmitter for AppHandle {
+    fn emit_line(&self, output: TranscriptionOutput) {
+        let _ = self.emit("transcription-output", output);
+    }
+
+    fn emit_segment(&self, segment: TranscriptSegment) {
+        let _ = self.emit("transcription-segment", segment);
+    }
+
+    fn emit_batch(&self, batch: TranscriptionOutputBatch) {
+        let _ = self.emit("transcription-output-batch", batch);
+    }
+
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        let _ = self.emit(event, payload);
+    }
+}
+
+/// Collects output lines and segments and emits them as
+/// `transcription-output-batch` events, or one `transcription-output` or
+/// `transcription-segment` event each when the `unbatched_output_events`
+/// compatibility setting is on. Every other event of the run goes through
+/// `emit`, which flushes first, so nothing overtakes earlier output.
+struct OutputSink<E: OutputEmitter> {
+    emitter: E,
+    unbatched: bool,
+    pending: Vec<TranscriptionOutput>,
+    pending_segments: Vec<TranscriptSegment>,
+}
+
+impl<E: OutputEmitter> OutputSink<E> {
+    fn new(emitter: E, unbatched: bool) -> Self {
+        Self {
+            emitter,
+            unbatched,
+            pending: Vec::new(),
+            pending_segments: Vec::new(),
+        }
+    }
+
+    fn push_segment(&mut self, segment: TranscriptSegment) {
+        self.push(TranscriptionOutput {
+            line: segment.to_line(),
+            is_error: false,
+            severity: Severity::Info,
+        });
+        if self.unbatched {
+            self.emitter.emit_segment(segment);
+        } else {
+            self.pending_segments.push(segment);
+        }
+    }
+
+    fn emit<S: Serialize + Clone>(&mut self, event: &str, payload: S) {
+        self.flush();
+        self.emitter.emit_event(event, payload);
+    }
+
+    fn push(&mut self, output: TranscriptionOutput) {
+        if self.unbatched {
+            self.emitter.emit_line(output);
+            return;
+        }
+
+        self.pending.push(output);
+        if self.pending.len() >= OUTPUT_BATCH_SIZE {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let lines = std::mem::take(&mut self.pending);
+        let segments = std::mem::take(&mut self.pending_segments);
+        self.emitter
+            .emit_batch(TranscriptionOutputBatch { lines, segments });
+    }
+}
+
+/// Runs the configured summary command, if any, in the background and saves
+/// its output next to the audio file.
+fn summarize_in_background(
+    app: AppHandle,
+    command: Option<SummaryCommand>,
+    audio_path: &Path,
+    output: &str,
+) {
+    let Some(command) = command else {
+        return;
+    };
+    let audio_path = audio_path.to_path_buf();
+    let transcript = transcript_text(output);
+
+    tokio::spawn(async move {
+        let result = async {
+            let summary = run_summary_command(&app, &command, &transcript).await?;
+            let file_name = summary_path(&audio_path);
+            let file_name = file_name.file_name().unwrap_or_default();
+            let path = resolve_output_beside(&app, &audio_path, file_name).await?;
+            tokio::fs::write(&path, &summary)
+                .await
+                .map_err(|e| format!("Failed to write summary: {}", e))?;
+            Ok::<_, String>((path, summary))
+        }
+        .await;
+
+        let complete = match result {
+            Ok((path, summary)) => SummaryComplete {
+                audio_path: audio_path.to_string_lossy().to_string(),
+                success: true,
+                summary_path: Some(path.to_string_lossy().to_string()),
+                summary: Some(summary),
+                error: None,
+            },
+            Err(err) => SummaryComplete {
+                audio_path: audio_path.to_string_lossy().to_string(),
+                success: false,
+                summary_path: None,
+                summary: None,
+                error: Some(err),
+            },
+        };
+        let _ = app.emit("summary-complete", complete);
+    });
+}
+
+async fn record_history(
+    app: &AppHandle,
+    audio_path: &Path,
+    model_name: &str,
+    formats: &[TranscriptFormat],
+    output_files: &[String],
+    job: Option<&JobRecord>,
+) {
+    let entry = HistoryEntry {
+        audio_path: audio_path.to_string_lossy().to_string(),
+        model_name: model_name.to_string(),
+        formats: formats.to_vec(),
+        completed_at_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        output_files: output_files.to_vec(),
+        job: job.map(HistoryJob::from),
+    };
+    if let Err(err) = app.state::<HistoryStore>().record(entry).await {
+        eprintln!("{}", err);
+    }
+}
+
+/// Reads the word timings from the full JSON whisper-cli wrote, then deletes
+/// the file unless JSON was one of the requested formats. A missing or
+/// malformed file only costs the timings, not the transcript.
+async fn take_word_timings(base: &Path, keep_json: bool) -> Vec<WordTiming> {
+    let path = output_file_path(base, TranscriptFormat::Json);
+    let words = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => parse_word_timings(&contents).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            Vec::new()
+        }),
+        Err(e) => {
+            eprintln!("Failed to read word timings: {}", e);
+            Vec::new()
+        }
+    };
+    if !keep_json {
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+    words
+}
+
+/// whisper-cli's `-otxt` puts every segment on a line of its own; rewrite it
+/// as paragraphs, the same as `convert_transcript_file` does.
+async fn write_txt_paragraphs(base: &Path, segments: &[TranscriptSegment]) {
+    let path = output_file_path(base, TranscriptFormat::Txt);
+    if segments.is_empty() || !path.is_file() {
+        return;
+    }
+
+    let cues: Vec<Cue> = segments
+        .iter()
+        .map(|segment| Cue {
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            text: segment.text.clone(),
+        })
+        .collect();
+    if let Err(e) = tokio::fs::write(&path, format_txt(&cues, DEFAULT_PARAGRAPH_GAP_MS)).await {
+        eprintln!("Failed to write transcript paragraphs: {}", e);
+    }
+}
+
+/// More threads or processors than logical cores only adds contention.
+pub fn validate_cpu_option(value: Option<u32>, what: &str) -> Result<(), String> {
+    let max = max_worker_count();
+    match value {
+        Some(count) if count == 0 || count as usize > max => {
+            Err(format!("{} must be between 1 and {}", what, max))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The options of `transcribe_audio`, so the queue can run its items
+/// through the same pipeline.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptionRequest {
+    pub audio_path: String,
+    /// With neither set, the default model from settings is used.
+    pub model_name: Option<String>,
+    pub model_path: Option<String>,
+    pub output_formats: Vec<TranscriptFormat>,
+    pub output_dir: Option<String>,
+    pub language: Option<String>,
+    pub grammar: Option<GrammarOptions>,
+    pub keywords: Vec<String>,
+    pub process_priority: Option<String>,
+    pub extra_args: Vec<String>,
+    pub strict: bool,
+    pub translate: bool,
+    pub threads: Option<u32>,
+    pub processors: Option<u32>,
+    pub word_timestamps: bool,
+}
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn transcribe_audio(
     app: AppHandle,
     audio_path: String,
-    model_name: String,
-    output_format: String,
+    model_name: Option<String>,
+    model_path: Option<String>,
+    output_format: OutputFormats,
+    output_dir: Option<String>,
     language: Option<String>,
+    grammar: Option<GrammarOptions>,
+    keywords: Option<Vec<String>>,
+    process_priority: Option<String>,
+    extra_args: Option<Vec<String>>,
+    strict: Option<bool>,
+    translate: Option<bool>,
+    threads: Option<u32>,
+    processors: Option<u32>,
+    word_timestamps: Option<bool>,
 ) -> Result<(), String> {
-    let audio_path = PathBuf::from(&audio_path);
-    if !audio_path.exists() {
-        return Err(format!("Audio file not found: {}", audio_path.display()));
+    let request = TranscriptionRequest {
+        audio_path,
+        model_name,
+        model_path,
+        output_formats: output_format.parse()?,
+        output_dir,
+        language,
+        grammar,
+        keywords: keywords.unwrap_or_default(),
+        process_priority,
+        extra_args: extra_args.unwrap_or_default(),
+        strict: strict.unwrap_or(false),
+        translate: translate.unwrap_or(false),
+        threads,
+        processors,
+        word_timestamps: word_timestamps.unwrap_or(false),
+    };
+    start_transcription(&app, request, |_| {}).await?;
+    Ok(())
+}
+
+fn send_complete(
+    app: &AppHandle,
+    done: &mut Option<oneshot::Sender<TranscriptionComplete>>,
+    complete: TranscriptionComplete,
+) {
+    let _ = app.emit("transcription-complete", complete.clone());
+    if let Some(done) = done.take() {
+        let _ = done.send(complete);
     }
+}
 
-    let model_path = get_model_path(&model_name);
-    if !model_path.exists() {
-        return Err(format!("Model '{}' not downloaded", model_name));
+/// Validates the request and starts the job, returning once whisper-cli is
+/// running or the result came from the cache. `on_job` gets the job id as
+/// soon as there is one, before waiting for a worker. The receiver yields
+/// the same payload as the `transcription-complete` event.
+pub async fn start_transcription<F>(
+    app: &AppHandle,
+    request: TranscriptionRequest,
+    on_job: F,
+) -> Result<oneshot::Receiver<TranscriptionComplete>, String>
+where
+    F: FnOnce(&str),
+{
+    let TranscriptionRequest {
+        audio_path,
+        model_name,
+        model_path,
+        output_formats,
+        output_dir,
+        language,
+        grammar,
+        keywords,
+        process_priority,
+        extra_args,
+        strict,
+        translate,
+        threads,
+        processors,
+        word_timestamps,
+    } = request;
+    let flags = app.state::<SidecarFlags>();
+    let jobs = app.state::<JobRegistry>();
+    let (done_tx, done_rx) = oneshot::channel();
+    let mut done = Some(done_tx);
+
+    let audio_path = resolve_input_file(&audio_path, "Audio file").await?;
+    // Rejects non-audio and empty files before a model is loaded for them.
+    // Without a duration there is simply no progress percentage.
+    let audio_info = probe_audio(app, &audio_path).await?;
+    let audio_duration_ms = Some(audio_info.duration_ms).filter(|&ms| ms > 0);
+    let settings = app.state::<SettingsManager>().get().await;
+    // Transcripts go next to the audio unless a folder is picked or set.
+    let output_dir = match output_dir.or_else(|| settings.output_dir.clone()) {
+        Some(dir) => resolve_output_dir(app, &dir).await?,
+        None => resolve_input_dir(app, &audio_path).await?,
+    };
+    let base = output_base(&audio_path, Some(&output_dir));
+
+    let model_name = match (model_name, &model_path) {
+        (None, None) => Some(settings.default_model.clone()).filter(|name| !name.is_empty()),
+        (model_name, _) => model_name,
+    };
+    let model_path = match (model_name, model_path) {
+        (Some(_), Some(_)) => {
+            return Err("Specify either a model name or a model path, not both".to_string())
+        }
+        (None, None) => return Err("No model specified".to_string()),
+        (Some(model_name), None) => {
+            let path = get_model_path(&model_name);
+            if !path.exists() {
+                return Err(format!("Model '{}' not downloaded", model_name));
+            }
+            path
+        }
+        (None, Some(path)) => resolve_model_file(&path).await?,
+    };
+
+    let model_size_mb = tokio::fs::metadata(&model_path)
+        .await
+        .map(|m| m.len() / (1024 * 1024))
+        .unwrap_or(0);
+    let ctx_model_path = model_path.clone();
+    let ctx_language = language.clone();
+    let warnings = tokio::task::spawn_blocking(move || {
+        run_preflight(&PreflightContext {
+            model_path: ctx_model_path,
+            language: ctx_language,
+            required_memory_mb: estimated_memory_mb(model_size_mb),
+            available_memory_mb: available_memory_mb(),
+            output_free_mb: free_space_mb(&output_dir),
+        })
+    })
+    .await
+    .map_err(|e| format!("Preflight checks failed: {}", e))?;
+    enforce_strict(&warnings, strict)?;
+
+    validate_cpu_option(threads, "Thread count")?;
+    validate_cpu_option(processors, "Processor count")?;
+
+    let priority = match process_priority.as_deref() {
+        Some(value) => ProcessPriority::parse(value)?,
+        None => ProcessPriority::Normal,
+    };
+
+    if !extra_args.is_empty() {
+        if !settings.advanced_mode {
+            return Err("Extra arguments require advanced mode".to_string());
+        }
+        let supported = flags.get(app).await;
+        validate_extra_args(&extra_args, &supported)?;
+
+        let unknown = unknown_extra_flags(&extra_args, &supported);
+        if !unknown.is_empty() {
+            let _ = app.emit(
+                "extra-args-warning",
+                ExtraArgsWarning {
+                    unknown_flags: unknown,
+                },
+            );
+        }
     }
 
+    let keywords = KeywordMatcher::new(&keywords)?;
+    let notify_keywords = !keywords.is_empty() && settings.keyword_notifications;
+
+    let grammar_file = match &grammar {
+        Some(grammar) => {
+            grammar.validate()?;
+            let supported = flags.get(app).await;
+            if !supported.iter().any(|f| f.flag == "--grammar") {
+                return Err("The installed whisper-cli does not support grammars".to_string());
+            }
+            Some(write_grammar_file(&grammar.grammar)?)
+        }
+        None => None,
+    };
+
+    if word_timestamps {
+        let supported = flags.get(app).await;
+        if !supported.iter().any(|f| f.flag == "--output-json-full") {
+            return Err("The installed whisper-cli does not support word timestamps".to_string());
+        }
+    }
+
+    let options = WhisperOptions {
+        output_formats,
+        output_base: Some(base.clone()),
+        word_timestamps,
+        language,
+        grammar_file: grammar_file.as_ref().map(|f| f.path().to_path_buf()),
+        grammar_rule: grammar.as_ref().map(GrammarOptions::rule_name),
+        grammar_penalty: grammar.as_ref().and_then(|g| g.penalty),
+        translate,
+        threads,
+        processors,
+        priority,
+        extra_args,
+    };
+
+    let model_label = model_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().trim_start_matches("ggml-").to_string())
+        .unwrap_or_default();
+
+    let args = build_args(&audio_path, &model_path, &options);
+    // Word timings come from the JSON file, which the cache doesn't keep.
+    let cache_key = if word_timestamps {
+        None
+    } else {
+        transcription_cache_key(&audio_path, &model_path, &args).ok()
+    };
+
+    // The cache only holds the transcript text, so it can't stand in for
+    // output files that have been moved or deleted since.
+    let output_files = existing_output_files(&base, &options.output_formats);
+    let outputs_present = output_files.len() == options.output_formats.len();
+    if let Some(key) = cache_key.as_ref().filter(|_| outputs_present) {
+        if let Some(output) = read_cached_transcription(key).await {
+            let _ = app.emit(
+                "cache-hit",
+                CacheHit {
+                    audio_path: audio_path.to_string_lossy().to_string(),
+                },
+            );
+            summarize_in_background(
+                app.clone(),
+                settings.summary_command.clone(),
+                &audio_path,
+                &output,
+            );
+            record_history(
+                app,
+                &audio_path,
+                &model_label,
+                &options.output_formats,
+                &output_files,
+                None,
+            )
+            .await;
+            send_complete(
+                app,
+                &mut done,
+                TranscriptionComplete {
+                    success: true,
+                    segments: parse_segments(&output),
+                    output_files,
+                    words: Vec::new(),
+                    detected_language: None,
+                    output,
+                    error: None,
+                    cancelled: false,
+                },
+            );
+            return Ok(done_rx);
+        }
+    }
+
+    let job = jobs.register(&model_path);
+    on_job(job.id());
+    let permit = app.state::<WorkerPool>().acquire().await;
+    let resources = app
+        .state::<ResourceScheduler>()
+        .start(WorkKind::Transcription, false, false, || {})
+        .await;
+    if job.is_cancelled() {
+        return Err("Cancelled by user".to_string());
+    }
+
+    // Kept until the job finishes; dropping it deletes the temporary WAV.
+    let converted = prepare_for_whisper(app, &audio_path).await?;
+    let input_path = converted
+        .as_ref()
+        .map(|file| file.path().to_path_buf())
+        .unwrap_or_else(|| audio_path.clone());
+
     let mut rx = run_transcription(
         app.clone(),
-        &audio_path,
+        &input_path,
         &model_path,
-        &output_format,
-        language.as_deref(),
+        &options,
+        &job,
+        audio_duration_ms,
     )
     .await?;
 
+    let output_formats = options.output_formats.clone();
+    // Sent before the forwarding task starts, so it always precedes output.
+    let record = JobRecord {
+        job_id: job.id().to_string(),
+        audio_path: audio_path.to_string_lossy().to_string(),
+        model_path: model_path.to_string_lossy().to_string(),
+        sidecar: get_sidecar_path().map(|p| p.to_string_lossy().to_string()),
+        argv: build_args(&input_path, &model_path, &options),
+        options,
+        audio: audio_info,
+        warnings,
+        started_at_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    };
+    jobs.record_started(record.clone());
+    let _ = app.emit("transcription-started", record.clone());
+
+    let started = Instant::now();
+
+    let unbatched = settings.unbatched_output_events;
+    let summary_command = settings.summary_command;
+    let job_id = job.id().to_string();
     let app_clone = app.clone();
     tokio::spawn(async move {
-        while let Some(event) = rx.recv().await {
+        let job = job;
+        let _converted = converted;
+        let _permit = permit;
+        let _resources = resources;
+        let _grammar_file = grammar_file;
+        let mut sink = OutputSink::new(app_clone.clone(), unbatched);
+        let mut flush_timer = tokio::time::interval(OUTPUT_FLUSH_INTERVAL);
+        // The end of the last segment stands in for the audio length.
+        let mut audio_ms = 0;
+        let mut detected_language = None;
+
+        loop {
+            let event = tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = flush_timer.tick() => {
+                    sink.flush();
+                    continue;
+                }
+            };
+
             match event {
                 TranscriptionEvent::Stdout(line) => {
-                    let _ = app_clone.emit(
-                        "transcription-output",
-                        TranscriptionOutput {
-                            line,
-                            is_error: false,
-                        },
-                    );
+                    sink.push(TranscriptionOutput {
+                        line,
+                        is_error: false,
+                        severity: Severity::Info,
+                    });
+                }
+                TranscriptionEvent::Segment(segment) => {
+                    audio_ms = audio_ms.max(segment.end_ms);
+                    let matches = keywords.find_matches(&segment.text);
+                    let text = segment.text.clone();
+                    let (start_ms, end_ms) = (segment.start_ms, segment.end_ms);
+                    sink.push_segment(segment);
+
+                    for keyword in matches {
+                        if notify_keywords {
+                            let _ = app_clone
+                                .notification()
+                                .builder()
+                                .title(format!("Keyword detected: {}", keyword))
+                                .body(&text)
+                                .show();
+                        }
+                        sink.emit(
+                            "keyword-detected",
+                            KeywordDetected {
+                                keyword,
+                                text: text.clone(),
+                                start_ms,
+                                end_ms,
+                            },
+                        );
+                    }
                 }
                 TranscriptionEvent::Stderr(line) => {
-                    let _ = app_clone.emit(
-                        "transcription-output",
-                        TranscriptionOutput {
-                            line,
-                            is_error: true,
-                        },
-                    );
+                    let severity = classify_stderr(&line);
+                    sink.push(TranscriptionOutput {
+                        line,
+                        is_error: severity == Severity::Error,
+                        severity,
+                    });
                 }
-                TranscriptionEvent::Completed(output) => {
-                    let _ = app_clone.emit(
-                        "transcription-complete",
+                TranscriptionEvent::Completed { output, segments } => {
+                    sink.flush();
+                    let words = if word_timestamps {
+                        let keep_json = output_formats.contains(&TranscriptFormat::Json);
+                        take_word_timings(&base, keep_json).await
+                    } else {
+                        Vec::new()
+                    };
+                    // whisper-cli exits cleanly on silent or empty audio, which
+                    // otherwise shows up as a successful but blank result. It
+                    // also leaves out or empties files it had nothing to put in.
+                    let output_files = existing_output_files(&base, &output_formats);
+                    let error = if transcript_text(&output).is_empty() {
+                        Some("No speech detected or no output produced".to_string())
+                    } else if output_files.len() < output_formats.len() {
+                        let missing: Vec<String> = output_formats
+                            .iter()
+                            .map(|format| output_file_path(&base, *format))
+                            .map(|path| path.to_string_lossy().to_string())
+                            .filter(|path| !output_files.contains(path))
+                            .collect();
+                        Some(format!(
+                            "No speech detected or no output produced: missing or empty {}",
+                            missing.join(", ")
+                        ))
+                    } else {
+                        None
+                    };
+                    let complete = if let Some(error) = error {
+                        TranscriptionComplete {
+                            success: false,
+                            output,
+                            segments,
+                            output_files,
+                            words,
+                            detected_language: detected_language.clone(),
+                            error: Some(error),
+                            cancelled: false,
+                        }
+                    } else {
+                        if let Some(key) = &cache_key {
+                            let _ = write_cached_transcription(key, &output).await;
+                        }
+                        let elapsed_ms = started.elapsed().as_millis() as u64;
+                        let statistics = app_clone.state::<StatisticsStore>();
+                        if let Err(err) = statistics
+                            .record_transcription(&model_label, audio_ms, elapsed_ms)
+                            .await
+                        {
+                            eprintln!("{}", err);
+                        }
+                        summarize_in_background(
+                            app_clone.clone(),
+                            summary_command.clone(),
+                            &audio_path,
+                            &output,
+                        );
+                        if output_formats.contains(&TranscriptFormat::Txt) {
+                            write_txt_paragraphs(&base, &segments).await;
+                        }
+                        record_history(
+                            &app_clone,
+                            &audio_path,
+                            &model_label,
+                            &output_formats,
+                            &output_files,
+                            Some(&record),
+                        )
+                        .await;
                         TranscriptionComplete {
                             success: true,
                             output,
+                            segments,
+                            output_files,
+                            words,
+                            detected_language: detected_language.clone(),
                             error: None,
+                            cancelled: false,
+                        }
+                    };
+                    send_complete(&app_clone, &mut done, complete);
+                }
+                TranscriptionEvent::LanguageDetected(detected) => {
+                    sink.emit("language-detected", detected.clone());
+                    detected_language = Some(detected);
+                }
+                TranscriptionEvent::Progress {
+                    percent,
+                    processed_ms,
+                    total_ms,
+                } => {
+                    let elapsed_ms = started.elapsed().as_millis() as u64;
+                    let eta_ms = (processed_ms > 0).then(|| {
+                        (elapsed_ms as f64 * (total_ms - processed_ms) as f64 / processed_ms as f64)
+                            as u64
+                    });
+                    sink.emit(
+                        "transcription-progress",
+                        TranscriptionProgress {
+                            job_id: job_id.clone(),
+                            percent,
+                            processed_ms,
+                            total_ms,
+                            elapsed_ms,
+                            eta_ms,
                         },
                     );
                 }
                 TranscriptionEvent::Error(err) => {
-                    let _ = app_clone.emit(
-                        "transcription-complete",
+                    sink.flush();
+                    send_complete(
+                        &app_clone,
+                        &mut done,
                         TranscriptionComplete {
                             success: false,
                             output: String::new(),
+                            segments: Vec::new(),
+                            output_files: Vec::new(),
+                            words: Vec::new(),
+                            detected_language: detected_language.clone(),
                             error: Some(err),
+                            cancelled: job.is_cancelled(),
                         },
                     );
                 }
             }
         }
+
+        sink.flush();
     });
 
-    Ok(())
+    Ok(done_rx)
+}
+
+#[tauri::command]
+pub async fn clear_transcription_cache() -> Result<(), String> {
+    clear_transcription_cache_dir().await
+}
+
+#[tauri::command]
+pub async fn cancel_transcription(
+    jobs: State<'_, JobRegistry>,
+    job_id: String,
+) -> Result<(), String> {
+    jobs.cancel(&job_id)
+}
+
+/// Emits `language-detection-started` with the job id `cancel_transcription`
+/// takes.
+#[tauri::command]
+pub async fn detect_language(
+    app: AppHandle,
+    pool: State<'_, WorkerPool>,
+    jobs: State<'_, JobRegistry>,
+    audio_path: String,
+    model_name: String,
+) -> Result<DetectedLanguage, String> {
+    let audio_path = resolve_input_file(&audio_path, "Audio file").await?;
+    let model_path = get_model_path(&model_name);
+    if !model_path.exists() {
+        return Err(format!("Model '{}' not downloaded", model_name));
+    }
+    if is_english_only(&model_name) {
+        return Err(format!(
+            "Model '{}' only supports English and can't detect languages",
+            model_name
+        ));
+    }
+
+    let job = jobs.register(&model_path);
+    let _ = app.emit(
+        "language-detection-started",
+        LanguageDetectionStarted {
+            job_id: job.id().to_string(),
+            audio_path: audio_path.to_string_lossy().to_string(),
+        },
+    );
+    let _permit = pool.acquire().await;
+    if job.is_cancelled() {
+        return Err("Cancelled by user".to_string());
+    }
+    let converted = prepare_for_whisper(&app, &audio_path).await?;
+    let input_path = converted
+        .as_ref()
+        .map_or(audio_path.as_path(), |f| f.path());
+
+    detect_language_with_sidecar(&app, input_path, &model_path, &job).await
+}
+
+#[tauri::command]
+pub async fn get_job(jobs: State<'_, JobRegistry>, job_id: String) -> Result<JobRecord, String> {
+    jobs.get(&job_id)
+        .ok_or_else(|| format!("Job '{}' not found", job_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Debug, PartialEq)]
+    enum Emitted {
+        Line(String),
+        Segment(usize),
+        /// Lines, then segment indices.
+        Batch(Vec<String>, Vec<usize>),
+        Event(String),
+    }
+
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<Emitted>>);
+
+    impl OutputEmitter for &Recorder {
+        fn emit_line(&self, output: TranscriptionOutput) {
+            self.0.borrow_mut().push(Emitted::Line(output.line));
+        }
+
+        fn emit_segment(&self, segment: TranscriptSegment) {
+            self.0.borrow_mut().push(Emitted::Segment(segment.index));
+        }
+
+        fn emit_batch(&self, batch: TranscriptionOutputBatch) {
+            let lines = batch.lines.into_iter().map(|l| l.line).collect();
+            let segments = batch.segments.into_iter().map(|s| s.index).collect();
+            self.0.borrow_mut().push(Emitted::Batch(lines, segments));
+        }
+
+        fn emit_event<S: Serialize + Clone>(&self, event: &str, _payload: S) {
+            self.0.borrow_mut().push(Emitted::Event(event.to_string()));
+        }
+    }
+
+    fn segment(index: usize, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            index,
+            start_ms: index as u64 * 1000,
+            end_ms: index as u64 * 1000 + 1000,
+            text: text.to_string(),
+        }
+    }
+
+    fn output(line: String) -> TranscriptionOutput {
+        TranscriptionOutput {
+            line,
+            is_error: false,
+            severity: Severity::Info,
+        }
+    }
+
+    fn lines(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("line {}", i)).collect()
+    }
+
+    #[test]
+    fn batches_keep_every_line_in_order() {
+        let recorder = Recorder::default();
+        let mut sink = OutputSink::new(&recorder, false);
+        let input = lines(OUTPUT_BATCH_SIZE * 2 + 7);
+        for line in &input {
+            sink.push(output(line.clone()));
+        }
+        sink.flush();
+
+        let emitted = recorder.0.take();
+        let sizes: Vec<usize> = emitted
+            .iter()
+            .map(|e| match e {
+                Emitted::Batch(lines, _) => lines.len(),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(sizes, [OUTPUT_BATCH_SIZE, OUTPUT_BATCH_SIZE, 7]);
+
+        let received: Vec<String> = emitted
+            .into_iter()
+            .flat_map(|e| match e {
+                Emitted::Batch(lines, _) => lines,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(received, input);
+    }
+
+    #[test]
+    fn flush_without_pending_lines_emits_nothing() {
+        let recorder = Recorder::default();
+        let mut sink = OutputSink::new(&recorder, false);
+        sink.flush();
+        sink.push(output("only".to_string()));
+        sink.flush();
+        sink.flush();
+
+        assert_eq!(
+            recorder.0.take(),
+            [Emitted::Batch(vec!["only".to_string()], Vec::new())]
+        );
+    }
+
+    #[test]
+    fn unbatched_mode_emits_each_line_immediately() {
+        let recorder = Recorder::default();
+        let mut sink = OutputSink::new(&recorder, true);
+        for line in lines(3) {
+            sink.push(output(line));
+        }
+        sink.flush();
+
+        let expected: Vec<Emitted> = lines(3).into_iter().map(Emitted::Line).collect();
+        assert_eq!(recorder.0.take(), expected);
+    }
+
+    #[test]
+    fn segments_are_batched_with_the_lines_around_them() {
+        let recorder = Recorder::default();
+        let mut sink = OutputSink::new(&recorder, false);
+        sink.push(output("loading model".to_string()));
+        sink.push_segment(segment(0, "Hello."));
+        sink.push(output("progress = 50%".to_string()));
+        sink.push_segment(segment(1, "Bye."));
+        sink.flush();
+
+        let first = segment(0, "Hello.").to_line();
+        let second = segment(1, "Bye.").to_line();
+        assert_eq!(
+            recorder.0.take(),
+            [Emitted::Batch(
+                vec![
+                    "loading model".to_string(),
+                    first,
+                    "progress = 50%".to_string(),
+                    second,
+                ],
+                vec![0, 1],
+            )]
+        );
+    }
+
+    #[test]
+    fn other_events_never_overtake_earlier_output() {
+        let recorder = Recorder::default();
+        let mut sink = OutputSink::new(&recorder, false);
+        sink.push(output("auto-detected language: de".to_string()));
+        sink.emit("language-detected", ());
+        sink.push_segment(segment(0, "Hallo."));
+        sink.emit("keyword-detected", ());
+        sink.emit("transcription-progress", ());
+        sink.push(output("done".to_string()));
+        sink.flush();
+
+        assert_eq!(
+            recorder.0.take(),
+            [
+                Emitted::Batch(vec!["auto-detected language: de".to_string()], Vec::new()),
+                Emitted::Event("language-detected".to_string()),
+                Emitted::Batch(vec![segment(0, "Hallo.").to_line()], vec![0]),
+                Emitted::Event("keyword-detected".to_string()),
+                Emitted::Event("transcription-progress".to_string()),
+                Emitted::Batch(vec!["done".to_string()], Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unbatched_mode_emits_segments_after_their_line() {
+        let recorder = Recorder::default();
+        let mut sink = OutputSink::new(&recorder, true);
+        sink.push(output("before".to_string()));
+        sink.push_segment(segment(0, "Hello."));
+        sink.emit("transcription-progress", ());
+
+        assert_eq!(
+            recorder.0.take(),
+            [
+                Emitted::Line("before".to_string()),
+                Emitted::Line(segment(0, "Hello.").to_line()),
+                Emitted::Segment(0),
+                Emitted::Event("transcription-progress".to_string()),
+            ]
+        );
+    }
 }
@@ -0,0 +1,29 @@
+use super::validate_worker_count;
+use crate::settings::SettingsManager;
+use crate::whisper::WorkerPool;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_worker_count(pool: State<'_, WorkerPool>) -> Result<usize, String> {
+    Ok(pool.count())
+}
+
+#[tauri::command]
+pub async fn set_worker_count(
+    app: AppHandle,
+    pool: State<'_, WorkerPool>,
+    settings: State<'_, SettingsManager>,
+    count: usize,
+) -> Result<(), String> {
+    validate_worker_count(count)?;
+
+    // Under the settings lock, as in `update_settings`.
+    settings
+        .update(&app, |s| {
+            s.worker_count = Some(count);
+            pool.resize(count);
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
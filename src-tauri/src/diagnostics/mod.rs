@@ -0,0 +1,5 @@
+pub mod redact;
+pub mod report;
+
+pub use redact::*;
+pub use report::*;
@@ -0,0 +1,140 @@
+use serde_json::Value;
+
+const REDACTED: &str = "[redacted]";
+
+/// Key words whose values are dropped outright, matched against the
+/// `_`-separated parts of a key so `api_key` matches but `keyword` doesn't.
+/// `args` covers the summary command, whose arguments commonly carry API
+/// keys.
+const SENSITIVE_WORDS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "key",
+    "auth",
+    "credentials",
+    "args",
+];
+
+fn is_sensitive_key(key: &str) -> bool {
+    key.to_ascii_lowercase()
+        .split(['_', '-'])
+        .any(|part| SENSITIVE_WORDS.contains(&part))
+}
+
+/// Replaces the user's home directory with `~`, since it usually contains
+/// their account name.
+pub fn redact_home(text: &str) -> String {
+    let Some(home) = dirs::home_dir() else {
+        return text.to_string();
+    };
+    let home = home.to_string_lossy();
+    if home.is_empty() {
+        return text.to_string();
+    }
+    text.replace(home.as_ref(), "~")
+}
+
+/// Redacts sensitive keys and home paths anywhere in a JSON value.
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive_key(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::String(text) => *text = redact_home(text),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sensitive_keys() {
+        for key in [
+            "token",
+            "access_token",
+            "hf-token",
+            "api_key",
+            "API_KEY",
+            "client_secret",
+            "password",
+            "auth",
+            "credentials",
+            "args",
+        ] {
+            assert!(is_sensitive_key(key), "{} should be sensitive", key);
+        }
+    }
+
+    #[test]
+    fn words_containing_a_sensitive_word_are_not_sensitive() {
+        for key in [
+            "keyword",
+            "keyword_notifications",
+            "keywords",
+            "author",
+            "argv_len",
+            "model_name",
+        ] {
+            assert!(!is_sensitive_key(key), "{} should not be sensitive", key);
+        }
+    }
+
+    #[test]
+    fn redacts_sensitive_values_at_any_depth() {
+        let mut value = json!({
+            "summary_command": {
+                "program": "summarize",
+                "args": ["--api-key", "sk-123"],
+            },
+            "models": [{ "name": "base", "api_key": "abc" }],
+            "keyword_notifications": true,
+            "token": null,
+        });
+        redact_value(&mut value);
+
+        assert_eq!(
+            value,
+            json!({
+                "summary_command": {
+                    "program": "summarize",
+                    "args": REDACTED,
+                },
+                "models": [{ "name": "base", "api_key": REDACTED }],
+                "keyword_notifications": true,
+                "token": null,
+            })
+        );
+    }
+
+    #[test]
+    fn replaces_the_home_directory_in_strings() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let home = home.to_string_lossy();
+        let mut value = json!({
+            "sidecar_path": format!("{}/bin/whisper-cli", home),
+            "paths": [format!("{}/models", home), "/opt/models"],
+        });
+        redact_value(&mut value);
+
+        assert_eq!(
+            value,
+            json!({
+                "sidecar_path": "~/bin/whisper-cli",
+                "paths": ["~/models", "/opt/models"],
+            })
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use super::{redact_home, redact_value};
+use crate::downloader::{check_ggml_magic, get_available_models, get_model_path};
+use crate::history::HistoryEntry;
+use crate::settings::Settings;
+use crate::subtitles::TranscriptFormat;
+use crate::whisper::{get_sidecar_path, FlagInfo, JobRecord};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppInfo {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub cpu: String,
+    pub cpu_cores: usize,
+    pub total_memory_mb: u64,
+    pub sidecar_path: Option<String>,
+    pub sidecar_available: bool,
+    pub worker_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDiagnostics {
+    pub name: String,
+    pub downloaded: bool,
+    pub size_bytes: Option<u64>,
+    pub file_valid: bool,
+}
+
+/// A job without its paths, arguments or grammar, which can name the user's
+/// files or carry text they typed.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobSummary {
+    pub job_id: String,
+    pub model: String,
+    pub running: bool,
+    pub started_at_ms: u64,
+    pub formats: Vec<TranscriptFormat>,
+    pub language: Option<String>,
+    pub translate: bool,
+    pub word_timestamps: bool,
+    pub threads: Option<u32>,
+    pub processors: Option<u32>,
+    pub extra_arg_count: usize,
+    pub warnings: Vec<&'static str>,
+}
+
+/// A history entry without the audio or transcript file names.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySummary {
+    pub model_name: String,
+    pub formats: Vec<TranscriptFormat>,
+    /// The audio file's extension, lowercased.
+    pub audio_format: Option<String>,
+    pub completed_at_ms: u64,
+    pub output_count: usize,
+}
+
+pub fn summarize_job(record: &JobRecord, running: bool) -> JobSummary {
+    let options = &record.options;
+    JobSummary {
+        job_id: record.job_id.clone(),
+        model: Path::new(&record.model_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        running,
+        started_at_ms: record.started_at_ms,
+        formats: options.output_formats.clone(),
+        language: options.language.clone(),
+        translate: options.translate,
+        word_timestamps: options.word_timestamps,
+        threads: options.threads,
+        processors: options.processors,
+        extra_arg_count: options.extra_args.len(),
+        warnings: record.warnings.iter().map(|w| w.code).collect(),
+    }
+}
+
+pub fn summarize_history(entry: &HistoryEntry) -> HistorySummary {
+    HistorySummary {
+        model_name: entry.model_name.clone(),
+        formats: entry.formats.clone(),
+        audio_format: Path::new(&entry.audio_path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase()),
+        completed_at_ms: entry.completed_at_ms,
+        output_count: entry.output_files.len(),
+    }
+}
+
+pub fn collect_app_info(app_version: String, worker_count: usize) -> AppInfo {
+    let system = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_cpu(CpuRefreshKind::nothing())
+            .with_memory(MemoryRefreshKind::nothing().with_ram()),
+    );
+    let sidecar_path = get_sidecar_path();
+
+    AppInfo {
+        app_version,
+        os: System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu: system
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .unwrap_or_default(),
+        cpu_cores: system.cpus().len(),
+        total_memory_mb: system.total_memory() / 1024 / 1024,
+        sidecar_available: sidecar_path.as_ref().is_some_and(|p| p.exists()),
+        sidecar_path: sidecar_path.map(|p| redact_home(&p.to_string_lossy())),
+        worker_count,
+    }
+}
+
+pub async fn collect_model_diagnostics() -> Vec<ModelDiagnostics> {
+    let mut models = Vec::new();
+    for model in get_available_models() {
+        let path = get_model_path(&model.name);
+        let metadata = tokio::fs::metadata(&path).await.ok();
+        let file_valid = metadata.is_some() && check_ggml_magic(&path).await.is_ok();
+        models.push(ModelDiagnostics {
+            name: model.name,
+            downloaded: metadata.is_some(),
+            size_bytes: metadata.map(|m| m.len()),
+            file_valid,
+        });
+    }
+    models
+}
+
+fn sanitized_settings(settings: &Settings) -> Result<serde_json::Value, String> {
+    let mut settings = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    redact_value(&mut settings);
+    Ok(settings)
+}
+
+/// Writes the diagnostics zip. Transcript text never goes in; only the
+/// metadata collected here does.
+pub fn write_diagnostics_zip(
+    path: &Path,
+    app_info: &AppInfo,
+    flags: &[FlagInfo],
+    models: &[ModelDiagnostics],
+    settings: &Settings,
+    jobs: &[JobSummary],
+    history: &[HistorySummary],
+) -> Result<(), String> {
+    let mut system = serde_json::json!({
+        "app": app_info,
+        "supported_flags": flags.iter().map(|f| &f.flag).collect::<Vec<_>>(),
+    });
+    redact_value(&mut system);
+    let mut models = serde_json::to_value(models)
+        .map_err(|e| format!("Failed to serialize model list: {}", e))?;
+    redact_value(&mut models);
+    let mut jobs = serde_json::json!({
+        "recent_jobs": jobs,
+        "history": history,
+    });
+    redact_value(&mut jobs);
+
+    let entries = [
+        ("system.json", system),
+        ("models.json", models),
+        ("settings.json", sanitized_settings(settings)?),
+        ("jobs.json", jobs),
+    ];
+
+    let file = std::fs::File::create(path)
+        .map_err(|e| format!("Failed to create diagnostics file: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, value) in entries {
+        let json = serde_json::to_string_pretty(&value)
+            .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+        zip.write_all(json.as_bytes())
+            .map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioInfo;
+    use crate::whisper::WhisperOptions;
+
+    #[test]
+    fn job_summary_leaves_out_paths_and_arguments() {
+        let record = JobRecord {
+            job_id: "job-1".to_string(),
+            audio_path: "/home/ana/interview.wav".to_string(),
+            model_path: "/home/ana/models/ggml-base.bin".to_string(),
+            sidecar: Some("/opt/whisper-cli".to_string()),
+            options: WhisperOptions {
+                output_formats: vec![TranscriptFormat::Srt],
+                language: Some("de".to_string()),
+                extra_args: vec!["--prompt".to_string(), "Ana's notes".to_string()],
+                ..Default::default()
+            },
+            argv: vec!["-f".to_string(), "/home/ana/interview.wav".to_string()],
+            audio: AudioInfo {
+                duration_ms: 1000,
+                sample_rate: 16_000,
+                channels: 1,
+                format: "pcm_s16le".to_string(),
+                size_bytes: 32_000,
+            },
+            warnings: Vec::new(),
+            started_at_ms: 42,
+        };
+        let summary = summarize_job(&record, true);
+        assert_eq!(summary.model, "ggml-base.bin");
+        assert_eq!(summary.extra_arg_count, 2);
+        assert!(summary.running);
+
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(!json.contains("ana"), "{}", json);
+    }
+
+    #[test]
+    fn history_summary_keeps_only_the_audio_extension() {
+        let entry = HistoryEntry {
+            audio_path: "/home/ana/Interview.MP3".to_string(),
+            model_name: "small".to_string(),
+            formats: vec![TranscriptFormat::Txt],
+            completed_at_ms: 7,
+            output_files: vec!["/home/ana/Interview.txt".to_string()],
+            job: None,
+        };
+        let summary = summarize_history(&entry);
+        assert_eq!(summary.audio_format.as_deref(), Some("mp3"));
+        assert_eq!(summary.output_count, 1);
+
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(!json.contains("Interview"), "{}", json);
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+type ActiveDownloads = Arc<Mutex<HashMap<String, watch::Sender<bool>>>>;
+
+/// Downloads in progress, keyed by model name, so they can be cancelled.
+#[derive(Default)]
+pub struct DownloadRegistry {
+    active: ActiveDownloads,
+}
+
+/// Removes its download from the registry when dropped.
+pub struct DownloadHandle {
+    model_name: String,
+    cancelled: watch::Receiver<bool>,
+    active: ActiveDownloads,
+}
+
+impl DownloadRegistry {
+    pub fn register(&self, model_name: &str) -> Result<DownloadHandle, String> {
+        let mut active = self.active.lock().unwrap();
+        if active.contains_key(model_name) {
+            return Err(format!("Model '{}' is already downloading", model_name));
+        }
+
+        let (sender, cancelled) = watch::channel(false);
+        active.insert(model_name.to_string(), sender);
+
+        Ok(DownloadHandle {
+            model_name: model_name.to_string(),
+            cancelled,
+            active: self.active.clone(),
+        })
+    }
+
+    /// Returns false if the model wasn't downloading.
+    pub fn cancel(&self, model_name: &str) -> bool {
+        match self.active.lock().unwrap().get(model_name) {
+            Some(sender) => {
+                sender.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl DownloadHandle {
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+
+    /// Resolves once the download is cancelled.
+    pub async fn cancelled(&mut self) {
+        let _ = self.cancelled.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Drop for DownloadHandle {
+    fn drop(&mut self) {
+        self.active.lock().unwrap().remove(&self.model_name);
+    }
+}
@@ -0,0 +1,33 @@
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use tokio::fs;
+use tokio::io::AsyncReadExt;
+
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Feeds a file into `hasher` in chunks so multi-gigabyte models are never
+/// held in memory.
+pub async fn hash_file_into(hasher: &mut Sha256, path: &Path) -> Result<(), String> {
+    let mut file = fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open file for hashing: {}", e))?;
+
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("Failed to read file for hashing: {}", e))?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// Lowercase hex SHA256 of a file.
+pub async fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    hash_file_into(&mut hasher, path).await?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
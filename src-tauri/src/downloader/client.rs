@@ -0,0 +1,31 @@
+use crate::settings::Settings;
+use reqwest::Client;
+use std::time::Duration;
+
+pub const INVALID_CERTS_WARNING: &str =
+    "TLS certificate validation is disabled for model downloads. Anyone on the network path \
+     can tamper with downloaded files; only enable this behind a trusted intercepting proxy.";
+
+pub const OFFLINE_MODE_ERROR: &str = "Offline mode is enabled; network access is disabled";
+
+/// Every network operation gets its client here, so offline mode is
+/// enforced in one place.
+pub fn build_client(settings: &Settings) -> Result<Client, String> {
+    if settings.offline_mode {
+        return Err(OFFLINE_MODE_ERROR.to_string());
+    }
+
+    let tuning = &settings.download_tuning;
+    let mut builder = Client::builder()
+        .tcp_keepalive(tuning.tcp_keepalive_secs.map(Duration::from_secs))
+        .pool_idle_timeout(tuning.pool_idle_timeout_secs.map(Duration::from_secs))
+        .http2_adaptive_window(tuning.http2_adaptive_window);
+
+    if settings.danger_accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
@@ -0,0 +1,126 @@
+use crate::downloader::{builtin_models, get_models_dir, lock_model_catalog, ModelInfo};
+use reqwest::Url;
+use std::path::PathBuf;
+use tokio::fs;
+
+pub fn get_custom_models_path() -> PathBuf {
+    get_models_dir().with_file_name("custom_models.json")
+}
+
+/// A missing or unreadable file means no custom models, like settings.
+pub fn load_custom_models() -> Vec<ModelInfo> {
+    std::fs::read_to_string(get_custom_models_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+async fn write_custom_models(models: &[ModelInfo]) -> Result<(), String> {
+    let path = get_custom_models_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(models)
+        .map_err(|e| format!("Failed to serialize custom models: {}", e))?;
+
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json)
+        .await
+        .map_err(|e| format!("Failed to write custom models: {}", e))?;
+    fs::rename(&temp_path, &path)
+        .await
+        .map_err(|e| format!("Failed to save custom models: {}", e))
+}
+
+/// Names become part of the model's file name, so only characters that are
+/// safe in a path on every platform are allowed.
+fn validate_model_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "Invalid model name '{}': use letters, digits, '-', '_' or '.'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn validate_model_url(url: &str) -> Result<(), String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid model URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(format!(
+            "Invalid model URL '{}': must be an http or https address",
+            url
+        ));
+    }
+    Ok(())
+}
+
+/// Downloads are compared against this, so a typo would reject every copy.
+fn validate_sha256(sha256: &str) -> Result<(), String> {
+    if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!(
+            "Invalid SHA256 '{}': expected 64 hexadecimal characters",
+            sha256
+        ));
+    }
+    Ok(())
+}
+
+pub async fn save_custom_model(model: ModelInfo) -> Result<(), String> {
+    validate_model_name(&model.name)?;
+    validate_model_url(&model.url)?;
+    if let Some(sha256) = &model.sha256 {
+        validate_sha256(sha256)?;
+    }
+    if builtin_models().iter().any(|m| m.name == model.name) {
+        return Err(format!("'{}' is the name of a built-in model", model.name));
+    }
+
+    let _catalog_lock = lock_model_catalog()?;
+    let mut models = load_custom_models();
+    if models.iter().any(|m| m.name == model.name) {
+        return Err(format!(
+            "A custom model named '{}' already exists",
+            model.name
+        ));
+    }
+    models.push(model);
+    write_custom_models(&models).await
+}
+
+/// Only the catalog entry goes; a downloaded file stays until deleted.
+pub async fn delete_custom_model(name: &str) -> Result<(), String> {
+    let _catalog_lock = lock_model_catalog()?;
+    let mut models = load_custom_models();
+    let before = models.len();
+    models.retain(|m| m.name != name);
+    if models.len() == before {
+        return Err(format!("No custom model named '{}'", name));
+    }
+    write_custom_models(&models).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_hex_digests_only() {
+        let digest = "a".repeat(64);
+        assert!(validate_sha256(&digest).is_ok());
+        assert!(validate_sha256(&"AbC0".repeat(16)).is_ok());
+        assert!(validate_sha256(&digest[1..]).is_err());
+        assert!(validate_sha256(&format!("{}0", digest)).is_err());
+        assert!(validate_sha256(&"g".repeat(64)).is_err());
+        assert!(validate_sha256("").is_err());
+    }
+}
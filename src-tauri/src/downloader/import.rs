@@ -0,0 +1,125 @@
+use crate::downloader::{
+    check_ggml_magic, get_available_models, get_import_model_path, get_model_path, get_models_dir,
+    lock_model, lock_model_catalog, sha256_file,
+};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Well under the smallest published model (tiny-q5_1, ~32MB); anything
+/// smaller is a truncated or unrelated file.
+const MIN_MODEL_SIZE_BYTES: u64 = 16 * 1024 * 1024;
+
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Brings an existing ggml file into the models directory as `model_name`.
+/// A hard link is tried first, so importing from the same drive is instant;
+/// otherwise the file is copied with progress reported as (copied, total).
+pub async fn import_model_file<F>(
+    source: &Path,
+    model_name: &str,
+    progress_callback: F,
+) -> Result<PathBuf, String>
+where
+    F: Fn(u64, u64),
+{
+    let model = get_available_models()
+        .into_iter()
+        .find(|m| m.name == model_name)
+        .ok_or_else(|| format!("Model '{}' not found", model_name))?;
+
+    let total = fs::metadata(source)
+        .await
+        .map_err(|e| format!("Failed to read model file: {}", e))?
+        .len();
+    if total < MIN_MODEL_SIZE_BYTES {
+        return Err(format!(
+            "'{}' is too small to be a whisper model",
+            source.display()
+        ));
+    }
+    check_ggml_magic(source).await?;
+
+    fs::create_dir_all(get_models_dir())
+        .await
+        .map_err(|e| format!("Failed to create models directory: {}", e))?;
+
+    let _lock = lock_model(model_name)?;
+
+    let model_path = get_model_path(model_name);
+    if model_path.exists() {
+        return Err(format!("Model '{}' is already downloaded", model_name));
+    }
+
+    let temp_path = get_import_model_path(model_name);
+    let _ = fs::remove_file(&temp_path).await;
+
+    if fs::hard_link(source, &temp_path).await.is_ok() {
+        progress_callback(total, total);
+    } else if let Err(err) = copy_with_progress(source, &temp_path, total, &progress_callback).await
+    {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(err);
+    }
+
+    if let Some(expected) = &model.sha256 {
+        if !sha256_file(&temp_path)
+            .await?
+            .eq_ignore_ascii_case(expected)
+        {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(format!(
+                "'{}' does not match the published checksum for model '{}'",
+                source.display(),
+                model_name
+            ));
+        }
+    }
+
+    let _catalog_lock = lock_model_catalog()?;
+    fs::rename(&temp_path, &model_path)
+        .await
+        .map_err(|e| format!("Error finalizing import: {}", e))?;
+
+    Ok(model_path)
+}
+
+async fn copy_with_progress<F>(
+    source: &Path,
+    destination: &Path,
+    total: u64,
+    progress_callback: &F,
+) -> Result<(), String>
+where
+    F: Fn(u64, u64),
+{
+    let mut reader = fs::File::open(source)
+        .await
+        .map_err(|e| format!("Failed to open model file: {}", e))?;
+    let mut writer = fs::File::create(destination)
+        .await
+        .map_err(|e| format!("Failed to create file: {}", e))?;
+
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut copied = 0;
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("Error reading model file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..read])
+            .await
+            .map_err(|e| format!("Error writing file: {}", e))?;
+        copied += read as u64;
+        progress_callback(copied, total);
+    }
+
+    writer
+        .flush()
+        .await
+        .map_err(|e| format!("Error flushing file: {}", e))
+}
@@ -0,0 +1,216 @@
+use super::get_models_dir;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// A lock older than this is treated as stale even if its PID is alive,
+/// since the PID may have been reused.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LockInfo {
+    pid: u32,
+    created_at_ms: u64,
+}
+
+/// An advisory lock file shared between app instances, removed on drop.
+pub struct LockFile {
+    path: PathBuf,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn is_process_running(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).is_some()
+}
+
+/// Unreadable lock files are left alone: a half-written one most likely
+/// belongs to an instance that is creating it right now.
+fn is_stale(path: &Path) -> bool {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let Ok(info) = serde_json::from_str::<LockInfo>(&contents) else {
+        return false;
+    };
+
+    let age = Duration::from_millis(now_ms().saturating_sub(info.created_at_ms));
+    !is_process_running(info.pid) || age > STALE_LOCK_AGE
+}
+
+impl LockFile {
+    /// Fails with `busy_message` if another task or instance holds the lock.
+    pub fn acquire(path: PathBuf, busy_message: &str) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create lock directory: {}", e))?;
+        }
+
+        // One retry after breaking a stale lock; losing that race to another
+        // instance is ordinary contention.
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let info = LockInfo {
+                        pid: std::process::id(),
+                        created_at_ms: now_ms(),
+                    };
+                    let json = serde_json::to_string(&info)
+                        .map_err(|e| format!("Failed to serialize lock: {}", e))?;
+                    file.write_all(json.as_bytes())
+                        .map_err(|e| format!("Failed to write lock file: {}", e))?;
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if !is_stale(&path) {
+                        break;
+                    }
+                    eprintln!("Removing stale lock file: {}", path.display());
+                    let _ = std::fs::remove_file(&path);
+                }
+                Err(e) => return Err(format!("Failed to create lock file: {}", e)),
+            }
+        }
+
+        Err(busy_message.to_string())
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Held for the whole of a download or delete of one model. The holder may
+/// be another task in this instance as well as another instance.
+pub fn lock_model(model_name: &str) -> Result<LockFile, String> {
+    LockFile::acquire(
+        get_models_dir().join(format!("ggml-{}.lock", model_name)),
+        &format!(
+            "Model '{}' is already being downloaded, imported or deleted; try again once that finishes",
+            model_name
+        ),
+    )
+}
+
+/// Held briefly while files are added to or removed from the models
+/// directory.
+pub fn lock_model_catalog() -> Result<LockFile, String> {
+    LockFile::acquire(
+        get_models_dir().join("catalog.lock"),
+        "The models folder is being updated; try again shortly",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+
+    const BUSY: &str = "busy";
+
+    fn write_lock(path: &Path, pid: u32, created_at_ms: u64) {
+        let info = LockInfo { pid, created_at_ms };
+        std::fs::write(path, serde_json::to_string(&info).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn only_one_of_two_concurrent_tasks_gets_the_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ggml-base.lock");
+        let start = Barrier::new(2);
+        let acquired = Barrier::new(2);
+
+        let results: Vec<Result<(), String>> = std::thread::scope(|scope| {
+            let tasks: Vec<_> = (0..2)
+                .map(|_| {
+                    scope.spawn(|| {
+                        start.wait();
+                        let lock = LockFile::acquire(path.clone(), BUSY);
+                        // Hold the winner's lock until both have tried.
+                        acquired.wait();
+                        lock.map(drop)
+                    })
+                })
+                .collect();
+            tasks.into_iter().map(|t| t.join().unwrap()).collect()
+        });
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results.contains(&Err(BUSY.to_string())));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn lock_is_released_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("catalog.lock");
+
+        let lock = LockFile::acquire(path.clone(), BUSY).unwrap();
+        assert!(LockFile::acquire(path.clone(), BUSY).is_err());
+        drop(lock);
+        assert!(LockFile::acquire(path, BUSY).is_ok());
+    }
+
+    #[test]
+    fn breaks_a_lock_left_by_a_dead_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ggml-base.lock");
+        // Far above any PID an OS hands out.
+        write_lock(&path, u32::MAX - 1, now_ms());
+
+        assert!(is_stale(&path));
+        let _lock = LockFile::acquire(path.clone(), BUSY).unwrap();
+        let info: LockInfo =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(info.pid, std::process::id());
+    }
+
+    #[test]
+    fn breaks_an_old_lock_even_if_its_pid_is_alive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ggml-base.lock");
+        write_lock(&path, std::process::id(), 0);
+
+        assert!(LockFile::acquire(path, BUSY).is_ok());
+    }
+
+    #[test]
+    fn keeps_a_fresh_lock_held_by_a_live_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ggml-base.lock");
+        write_lock(&path, std::process::id(), now_ms());
+
+        assert_eq!(
+            LockFile::acquire(path.clone(), BUSY).err().as_deref(),
+            Some(BUSY)
+        );
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn leaves_unreadable_lock_files_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ggml-base.lock");
+        std::fs::write(&path, "{\"pid\":").unwrap();
+
+        assert!(!is_stale(&path));
+        assert!(LockFile::acquire(path, BUSY).is_err());
+    }
+}
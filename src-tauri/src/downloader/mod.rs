@@ -1,3 +1,17 @@
+pub mod cancel;
+pub mod checksum;
+pub mod client;
+pub mod custom;
+pub mod import;
+pub mod lock;
 pub mod models;
+pub mod queue;
 
+pub use cancel::*;
+pub use checksum::*;
+pub use client::*;
+pub use custom::*;
+pub use import::*;
+pub use lock::*;
 pub use models::*;
+pub use queue::*;
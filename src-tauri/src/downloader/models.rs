@@ -1,9 +1,18 @@
+use crate::downloader::{
+    build_client, hash_file_into, load_custom_models, lock_model, lock_model_catalog, sha256_file,
+    DownloadHandle,
+};
+use crate::paths::normalize_path;
+use crate::settings::Settings;
+use crate::whisper::free_space_mb;
 use futures_util::StreamExt;
-use reqwest::Client;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::{Client, Response, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
@@ -12,16 +21,63 @@ pub struct ModelInfo {
     pub size_mb: u64,
     pub description: String,
     pub url: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub release_notes: Option<String>,
+    /// Lowercase hex digest of the published file. `None` when no hash is
+    /// known, in which case downloads are not verified.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Set on quantized builds, e.g. `q5_0`; `name` is then the parent
+    /// model's name with `-<quantization>` appended.
+    #[serde(default)]
+    pub quantization: Option<String>,
 }
 
+/// Quantized builds published alongside the full-precision models, as
+/// (parent model, quantization, size in MB).
+const QUANTIZED_MODELS: &[(&str, &str, u64)] = &[
+    ("tiny", "q5_1", 32),
+    ("tiny", "q8_0", 44),
+    ("base", "q5_1", 60),
+    ("base", "q8_0", 82),
+    ("small", "q5_1", 190),
+    ("small", "q8_0", 264),
+    ("medium", "q5_0", 539),
+    ("medium", "q8_0", 823),
+    ("large-v3", "q5_0", 1080),
+    ("large-v3-turbo", "q5_0", 574),
+    ("large-v3-turbo", "q8_0", 874),
+];
+
+/// Built-in models followed by the user's custom ones.
 pub fn get_available_models() -> Vec<ModelInfo> {
-    vec![
+    let mut models = builtin_models();
+    let custom = load_custom_models()
+        .into_iter()
+        .filter(|custom| !models.iter().any(|m| m.name == custom.name))
+        .collect::<Vec<_>>();
+    models.extend(custom);
+    models
+}
+
+/// The digests Hugging Face publishes for these files haven't been copied
+/// in yet, so every `sha256` here is `None`: built-in downloads are not
+/// verified and `verify_model` reports their hash without a verdict. Fill
+/// them in from each file's LFS pointer rather than hashing a download.
+pub fn builtin_models() -> Vec<ModelInfo> {
+    let mut models = vec![
         ModelInfo {
             name: "tiny".to_string(),
             display_name: "Tiny".to_string(),
             size_mb: 75,
             description: "Fastest, lowest accuracy".to_string(),
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin".to_string(),
+            version: None,
+            release_notes: None,
+            sha256: None,
+            quantization: None,
         },
         ModelInfo {
             name: "base".to_string(),
@@ -29,6 +85,10 @@ pub fn get_available_models() -> Vec<ModelInfo> {
             size_mb: 148,
             description: "Fast, good for simple audio".to_string(),
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin".to_string(),
+            version: None,
+            release_notes: None,
+            sha256: None,
+            quantization: None,
         },
         ModelInfo {
             name: "small".to_string(),
@@ -36,6 +96,10 @@ pub fn get_available_models() -> Vec<ModelInfo> {
             size_mb: 488,
             description: "Balanced speed and accuracy".to_string(),
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin".to_string(),
+            version: None,
+            release_notes: None,
+            sha256: None,
+            quantization: None,
         },
         ModelInfo {
             name: "medium".to_string(),
@@ -43,6 +107,10 @@ pub fn get_available_models() -> Vec<ModelInfo> {
             size_mb: 1500,
             description: "High accuracy, slower".to_string(),
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin".to_string(),
+            version: None,
+            release_notes: None,
+            sha256: None,
+            quantization: None,
         },
         ModelInfo {
             name: "large-v3".to_string(),
@@ -50,6 +118,10 @@ pub fn get_available_models() -> Vec<ModelInfo> {
             size_mb: 3000,
             description: "Best accuracy, slowest".to_string(),
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3.bin".to_string(),
+            version: None,
+            release_notes: None,
+            sha256: None,
+            quantization: None,
         },
         ModelInfo {
             name: "large-v3-turbo".to_string(),
@@ -57,8 +129,69 @@ pub fn get_available_models() -> Vec<ModelInfo> {
             size_mb: 1600,
             description: "Fast and accurate".to_string(),
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo.bin".to_string(),
+            version: None,
+            release_notes: None,
+            sha256: None,
+            quantization: None,
         },
-    ]
+    ];
+
+    let quantized: Vec<ModelInfo> = QUANTIZED_MODELS
+        .iter()
+        .filter_map(|&(parent, quantization, size_mb)| {
+            let parent = models.iter().find(|m| m.name == parent)?;
+            let name = format!("{}-{}", parent.name, quantization);
+            Some(ModelInfo {
+                display_name: format!("{} ({})", parent.display_name, quantization.to_uppercase()),
+                size_mb,
+                description: format!(
+                    "{}; {}",
+                    parent.description,
+                    quantization_tradeoff(quantization)
+                ),
+                url: format!(
+                    "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{}.bin",
+                    name
+                ),
+                version: None,
+                release_notes: None,
+                sha256: None,
+                quantization: Some(quantization.to_string()),
+                name,
+            })
+        })
+        .collect();
+    models.extend(quantized);
+
+    models
+}
+
+fn quantization_tradeoff(quantization: &str) -> &'static str {
+    if quantization.starts_with("q8") {
+        "8-bit quantized: smaller and faster, close to full accuracy"
+    } else {
+        "5-bit quantized: much smaller and faster, slightly less accurate"
+    }
+}
+
+/// Splits `medium-q5_0` into `("medium", Some("q5_0"))`.
+pub fn split_quantization(model_name: &str) -> (&str, Option<&str>) {
+    match model_name.rsplit_once('-') {
+        Some((parent, suffix)) if suffix.starts_with('q') && suffix.contains('_') => {
+            (parent, Some(suffix))
+        }
+        _ => (model_name, None),
+    }
+}
+
+/// Rough working-set size while transcribing, fitted to the memory figures
+/// whisper.cpp publishes for each model size.
+pub fn estimated_memory_mb(model_size_mb: u64) -> u64 {
+    model_size_mb + model_size_mb / 4 + 200
+}
+
+pub fn is_english_only(model_name: &str) -> bool {
+    split_quantization(model_name).0.ends_with(".en")
 }
 
 pub fn get_models_dir() -> PathBuf {
@@ -72,13 +205,104 @@ pub fn get_model_path(model_name: &str) -> PathBuf {
     get_models_dir().join(format!("ggml-{}.bin", model_name))
 }
 
+pub fn get_temp_model_path(model_name: &str) -> PathBuf {
+    get_model_path(model_name).with_extension("bin.tmp")
+}
+
+/// Kept apart from the download's `.bin.tmp`, so an import can't clobber a
+/// partial download that could still be resumed.
+pub fn get_import_model_path(model_name: &str) -> PathBuf {
+    get_model_path(model_name).with_extension("bin.import")
+}
+
+/// Every whisper.cpp model starts with the little-endian `ggml` magic.
+const GGML_MAGIC: [u8; 4] = *b"lmgg";
+
+pub async fn check_ggml_magic(path: &Path) -> Result<(), String> {
+    let mut file = fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open model file: {}", e))?;
+
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)
+        .await
+        .map_err(|_| format!("'{}' is not a ggml model file", path.display()))?;
+
+    if magic != GGML_MAGIC {
+        return Err(format!("'{}' is not a ggml model file", path.display()));
+    }
+
+    Ok(())
+}
+
+/// Resolves a model file outside the managed models directory.
+pub async fn resolve_model_file(path: &str) -> Result<PathBuf, String> {
+    let path = fs::canonicalize(path)
+        .await
+        .map_err(|e| format!("Model file not found: {} ({})", path, e))?;
+
+    if !path.is_file() {
+        return Err(format!("Model path is not a file: {}", path.display()));
+    }
+
+    check_ggml_magic(&path).await?;
+    Ok(normalize_path(path))
+}
+
 pub async fn is_model_downloaded(model_name: &str) -> bool {
     let path = get_model_path(model_name);
     path.exists()
 }
 
+/// Asks for a single byte; servers that honour ranges answer 206.
+pub async fn supports_range_requests(client: &Client, url: &str) -> Result<bool, String> {
+    let response = client
+        .get(url)
+        .header(RANGE, "bytes=0-0")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach download server: {}", e))?;
+
+    Ok(response.status() == StatusCode::PARTIAL_CONTENT)
+}
+
+/// Room left over after a download so the disk isn't filled to the brim.
+const DISK_SPACE_MARGIN_MB: u64 = 200;
+
+/// Fails if the volume holding `dir` can't fit `needed_mb` plus a margin.
+/// Passes when the free space can't be determined rather than blocking the
+/// download on a volume `sysinfo` doesn't list.
+pub fn ensure_disk_space(dir: &Path, needed_mb: u64) -> Result<(), String> {
+    let Some(available_mb) = free_space_mb(dir) else {
+        return Ok(());
+    };
+    let needed_mb = needed_mb + DISK_SPACE_MARGIN_MB;
+    if available_mb < needed_mb {
+        return Err(format!(
+            "Not enough disk space: need {}MB, have {}MB",
+            needed_mb, available_mb
+        ));
+    }
+    Ok(())
+}
+
+/// The full length from a 416's `Content-Range: bytes */<length>`.
+fn content_range_total(response: &Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes */")?
+        .trim()
+        .parse()
+        .ok()
+}
+
 pub async fn download_model<F>(
     model_name: &str,
+    settings: &Settings,
+    handle: &mut DownloadHandle,
     progress_callback: F,
 ) -> Result<PathBuf, String>
 where
@@ -90,39 +314,121 @@ where
         .find(|m| m.name == model_name)
         .ok_or_else(|| format!("Model '{}' not found", model_name))?;
 
+    let client = build_client(settings)?;
+
     let models_dir = get_models_dir();
     fs::create_dir_all(&models_dir)
         .await
         .map_err(|e| format!("Failed to create models directory: {}", e))?;
 
+    let _lock = lock_model(model_name)?;
+
     let model_path = get_model_path(model_name);
-    let temp_path = model_path.with_extension("bin.tmp");
+    let temp_path = get_temp_model_path(model_name);
 
-    let client = Client::new();
-    let response = client
-        .get(&model.url)
+    let offset = fs::metadata(&temp_path).await.map(|m| m.len()).unwrap_or(0);
+    // A partial file already holds part of the model.
+    ensure_disk_space(
+        &models_dir,
+        model.size_mb.saturating_sub(offset / (1024 * 1024)),
+    )?;
+
+    let mut request = client.get(&model.url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request
         .send()
         .await
         .map_err(|e| format!("Failed to start download: {}", e))?;
 
+    // A 416 means the partial file is already as long as the model, e.g.
+    // because the last run stopped right before the rename. Keep it when it
+    // checks out, otherwise throw it away and download from scratch.
+    if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        let complete = content_range_total(&response) == Some(offset)
+            && match &model.sha256 {
+                Some(expected) => sha256_file(&temp_path)
+                    .await
+                    .is_ok_and(|actual| actual.eq_ignore_ascii_case(expected)),
+                None => true,
+            };
+        if complete {
+            progress_callback(offset, offset);
+            let _catalog_lock = lock_model_catalog()?;
+            fs::rename(&temp_path, &model_path)
+                .await
+                .map_err(|e| format!("Error finalizing download: {}", e))?;
+            return Ok(model_path);
+        }
+
+        fs::remove_file(&temp_path)
+            .await
+            .map_err(|e| format!("Failed to remove partial download: {}", e))?;
+        response = client
+            .get(&model.url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to start download: {}", e))?;
+    }
+
     if !response.status().is_success() {
         return Err(format!("Download failed with status: {}", response.status()));
     }
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded: u64 = 0;
+    // A 200 means the server ignored the range, so start over.
+    let resuming = offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded: u64 = if resuming { offset } else { 0 };
+    let total_size = response
+        .content_length()
+        .map(|len| len + downloaded)
+        .unwrap_or(0);
 
-    let mut file = fs::File::create(&temp_path)
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resuming)
+        .truncate(!resuming)
+        .open(&temp_path)
         .await
         .map_err(|e| format!("Failed to create file: {}", e))?;
 
+    // Hashed as it streams; a resumed download first rehashes what is
+    // already on disk.
+    let mut hasher = match &model.sha256 {
+        Some(_) => {
+            let mut hasher = Sha256::new();
+            if resuming {
+                hash_file_into(&mut hasher, &temp_path).await?;
+            }
+            Some(hasher)
+        }
+        None => None,
+    };
+
+    progress_callback(downloaded, total_size);
+
     let mut stream = response.bytes_stream();
 
-    while let Some(chunk) = stream.next().await {
+    loop {
+        let chunk = tokio::select! {
+            chunk = stream.next() => chunk,
+            _ = handle.cancelled() => {
+                drop(file);
+                let _ = fs::remove_file(&temp_path).await;
+                return Err(format!("Download of '{}' was cancelled", model_name));
+            }
+        };
+        let Some(chunk) = chunk else {
+            break;
+        };
         let chunk = chunk.map_err(|e| format!("Error downloading: {}", e))?;
         file.write_all(&chunk)
             .await
             .map_err(|e| format!("Error writing file: {}", e))?;
+        if let Some(hasher) = hasher.as_mut() {
+            hasher.update(&chunk);
+        }
 
         downloaded += chunk.len() as u64;
         progress_callback(downloaded, total_size);
@@ -131,7 +437,19 @@ where
     file.flush()
         .await
         .map_err(|e| format!("Error flushing file: {}", e))?;
+    drop(file);
+
+    if let (Some(hasher), Some(expected)) = (hasher, &model.sha256) {
+        if !format!("{:x}", hasher.finalize()).eq_ignore_ascii_case(expected) {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(format!(
+                "Checksum verification failed for model '{}'",
+                model_name
+            ));
+        }
+    }
 
+    let _catalog_lock = lock_model_catalog()?;
     fs::rename(&temp_path, &model_path)
         .await
         .map_err(|e| format!("Error finalizing download: {}", e))?;
@@ -0,0 +1,78 @@
+use crate::whisper::{WorkerPermit, WorkerPool};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 2;
+pub const MAX_DOWNLOAD_CONCURRENCY: usize = 8;
+
+/// Limits how many models download at once so parallel downloads don't
+/// split the bandwidth until none of them make progress. Downloads beyond the
+/// limit wait their turn in the order they were requested.
+pub struct DownloadQueue {
+    slots: WorkerPool,
+    waiting: Mutex<VecDeque<String>>,
+}
+
+/// Takes the model out of the waiting list when it gets a slot or the wait
+/// is abandoned, and reports the new order to everyone still waiting.
+struct Waiting<'a, F: Fn(&[String])> {
+    queue: &'a DownloadQueue,
+    model_name: &'a str,
+    on_change: F,
+}
+
+impl Default for DownloadQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_DOWNLOAD_CONCURRENCY)
+    }
+}
+
+impl DownloadQueue {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            slots: WorkerPool::new(limit),
+            waiting: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn resize(&self, limit: usize) {
+        self.slots.resize(limit);
+    }
+
+    /// Waits for a download slot. `on_change` is called with the waiting
+    /// models, in order, whenever that list changes while this download is
+    /// queued. It isn't called at all if a slot is free straight away.
+    pub async fn acquire<F>(&self, model_name: &str, on_change: F) -> WorkerPermit
+    where
+        F: Fn(&[String]),
+    {
+        if let Some(permit) = self.slots.try_acquire() {
+            return permit;
+        }
+
+        let waiting = {
+            let mut queue = self.waiting.lock().unwrap();
+            queue.push_back(model_name.to_string());
+            on_change(queue.make_contiguous());
+            Waiting {
+                queue: self,
+                model_name,
+                on_change,
+            }
+        };
+
+        let permit = self.slots.acquire().await;
+        drop(waiting);
+        permit
+    }
+}
+
+impl<F: Fn(&[String])> Drop for Waiting<'_, F> {
+    fn drop(&mut self) {
+        let mut queue = self.queue.waiting.lock().unwrap();
+        if let Some(index) = queue.iter().position(|name| name == self.model_name) {
+            queue.remove(index);
+        }
+        (self.on_change)(queue.make_contiguous());
+    }
+}
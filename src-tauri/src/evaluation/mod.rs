@@ -0,0 +1,3 @@
+pub mod wer;
+
+pub use wer::*;
@@ -0,0 +1,85 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ErrorRate {
+    pub errors: usize,
+    pub reference_len: usize,
+    pub rate: f64,
+}
+
+impl ErrorRate {
+    pub fn new(errors: usize, reference_len: usize) -> Self {
+        let rate = if reference_len > 0 {
+            errors as f64 / reference_len as f64
+        } else if errors > 0 {
+            1.0
+        } else {
+            0.0
+        };
+
+        Self {
+            errors,
+            reference_len,
+            rate,
+        }
+    }
+
+    /// Pools error counts so the aggregate is weighted by reference length
+    /// instead of averaging per-sample rates.
+    pub fn combine(rates: impl IntoIterator<Item = ErrorRate>) -> Self {
+        let (errors, reference_len) = rates
+            .into_iter()
+            .fold((0, 0), |(e, n), r| (e + r.errors, n + r.reference_len));
+        Self::new(errors, reference_len)
+    }
+}
+
+/// Lowercases and drops punctuation so casing and punctuation differences
+/// between whisper output and hand-written references aren't counted.
+pub fn normalize_text(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c.is_whitespace() || c == '\'' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn edit_distance<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    let mut current = vec![0; hypothesis.len() + 1];
+
+    for (i, r) in reference.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, h) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(r != h);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[hypothesis.len()]
+}
+
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> ErrorRate {
+    let reference = normalize_text(reference);
+    let hypothesis = normalize_text(hypothesis);
+    let reference: Vec<&str> = reference.split_whitespace().collect();
+    let hypothesis: Vec<&str> = hypothesis.split_whitespace().collect();
+
+    ErrorRate::new(edit_distance(&reference, &hypothesis), reference.len())
+}
+
+pub fn character_error_rate(reference: &str, hypothesis: &str) -> ErrorRate {
+    let reference: Vec<char> = normalize_text(reference).chars().collect();
+    let hypothesis: Vec<char> = normalize_text(hypothesis).chars().collect();
+
+    ErrorRate::new(edit_distance(&reference, &hypothesis), reference.len())
+}
@@ -0,0 +1,169 @@
+use crate::audio::AudioInfo;
+use crate::subtitles::TranscriptFormat;
+use crate::whisper::{JobRecord, WhisperOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+/// Older entries are dropped once the history grows past this.
+pub const MAX_HISTORY_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub audio_path: String,
+    pub model_name: String,
+    pub formats: Vec<TranscriptFormat>,
+    pub completed_at_ms: u64,
+    pub output_files: Vec<String>,
+    /// How whisper-cli was run. `None` for cache hits and older entries.
+    #[serde(default)]
+    pub job: Option<HistoryJob>,
+}
+
+/// The parts of a `JobRecord` needed to rerun it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryJob {
+    pub job_id: String,
+    pub sidecar: Option<String>,
+    /// Includes the raw extra arguments.
+    pub options: WhisperOptions,
+    pub argv: Vec<String>,
+    pub audio: AudioInfo,
+}
+
+impl From<&JobRecord> for HistoryJob {
+    fn from(record: &JobRecord) -> Self {
+        Self {
+            job_id: record.job_id.clone(),
+            sidecar: record.sidecar.clone(),
+            options: record.options.clone(),
+            argv: record.argv.clone(),
+            audio: record.audio.clone(),
+        }
+    }
+}
+
+pub fn get_history_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.whisper-gui.app")
+        .join("history.json")
+}
+
+/// Successful transcriptions, oldest first, kept in memory and written
+/// through on every change.
+pub struct HistoryStore {
+    entries: Mutex<VecDeque<HistoryEntry>>,
+}
+
+impl HistoryStore {
+    /// An unreadable history starts empty rather than blocking startup.
+    pub fn load() -> Self {
+        let entries = std::fs::read_to_string(get_history_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            entries: Mutex::new(entries),
+        }
+    }
+
+    pub async fn record(&self, entry: HistoryEntry) -> Result<(), String> {
+        let mut entries = self.entries.lock().await;
+        entries.push_back(entry);
+        while entries.len() > MAX_HISTORY_ENTRIES {
+            entries.pop_front();
+        }
+        write_history(&entries).await
+    }
+
+    /// Newest first.
+    pub async fn entries(&self) -> Vec<HistoryEntry> {
+        self.entries.lock().await.iter().rev().cloned().collect()
+    }
+
+    pub async fn clear(&self) -> Result<(), String> {
+        let mut entries = self.entries.lock().await;
+        entries.clear();
+        write_history(&entries).await
+    }
+}
+
+async fn write_history(entries: &VecDeque<HistoryEntry>) -> Result<(), String> {
+    let path = get_history_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create history directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string(entries)
+        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json)
+        .await
+        .map_err(|e| format!("Failed to write history: {}", e))?;
+
+    fs::rename(&temp_path, &path)
+        .await
+        .map_err(|e| format!("Failed to save history: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip_with_extra_args() {
+        let entry = HistoryEntry {
+            audio_path: "/audio/talk.mp3".to_string(),
+            model_name: "base".to_string(),
+            formats: vec![TranscriptFormat::Srt],
+            completed_at_ms: 7,
+            output_files: vec!["/audio/talk.srt".to_string()],
+            job: Some(HistoryJob {
+                job_id: "job-1".to_string(),
+                sidecar: Some("/opt/whisper-cli".to_string()),
+                options: WhisperOptions {
+                    output_formats: vec![TranscriptFormat::Srt],
+                    extra_args: vec!["--prompt".to_string(), "Ana, Bo".to_string()],
+                    threads: Some(4),
+                    ..Default::default()
+                },
+                argv: vec![
+                    "-m".to_string(),
+                    "ggml-base.bin".to_string(),
+                    "--prompt".to_string(),
+                    "Ana, Bo".to_string(),
+                ],
+                audio: AudioInfo {
+                    duration_ms: 61_000,
+                    sample_rate: 44_100,
+                    channels: 2,
+                    format: "mp3".to_string(),
+                    size_bytes: 1024,
+                },
+            }),
+        };
+
+        let json = serde_json::to_string(&entry).unwrap();
+        let read: HistoryEntry = serde_json::from_str(&json).unwrap();
+        let job = read.job.unwrap();
+        assert_eq!(job.options.extra_args, ["--prompt", "Ana, Bo"]);
+        assert_eq!(job.argv, entry.job.as_ref().unwrap().argv);
+        assert_eq!(job.options.threads, Some(4));
+        assert_eq!(job.audio.duration_ms, 61_000);
+    }
+
+    #[test]
+    fn reads_entries_written_before_jobs_were_recorded() {
+        let json = r#"[{"audio_path":"/a.wav","model_name":"base","formats":["txt"],"completed_at_ms":1,"output_files":[]}]"#;
+        let entries: VecDeque<HistoryEntry> = serde_json::from_str(json).unwrap();
+        assert!(entries[0].job.is_none());
+    }
+}
@@ -1,23 +1,138 @@
+mod audio;
+mod cache;
 mod commands;
+mod diagnostics;
 mod downloader;
+mod evaluation;
+mod history;
+mod paths;
+mod queue;
+mod scheduler;
+mod settings;
+mod statistics;
+mod subtitles;
+mod summary;
 mod whisper;
 
 use commands::{
-    delete_model, download_model_command, get_model_path_command, list_models, transcribe_audio,
+    add_custom_model, analyze_audio_levels, audio_info, cancel_download, cancel_transcription,
+    clear_history, clear_queue, clear_transcription_cache, clip_transcript,
+    convert_transcript_file, delete_model, detect_language, download_model_command,
+    enqueue_transcriptions, evaluate_model, generate_diagnostics, get_accept_invalid_certs,
+    get_advanced_mode, get_app_info, get_audio_info, get_download_resume_info, get_download_tuning,
+    get_history, get_job, get_keyword_notifications, get_model_details, get_model_path_command,
+    get_model_readiness, get_offline_mode, get_queue, get_serialize_heavy_work, get_settings,
+    get_statistics, get_summary_command, get_supported_flags, get_system_info,
+    get_unbatched_output_events, get_worker_count, import_model, kill_orphaned_processes,
+    list_models, remove_custom_model, remove_from_queue, render_subtitle_preview,
+    rescale_subtitle_timing, reset_statistics, save_settings, set_accept_invalid_certs,
+    set_advanced_mode, set_download_tuning, set_keyword_notifications, set_offline_mode,
+    set_serialize_heavy_work, set_summary_command, set_unbatched_output_events, set_worker_count,
+    transcribe_audio, transcribe_batch, update_settings, verify_model,
+};
+use downloader::{
+    DownloadQueue, DownloadRegistry, DEFAULT_DOWNLOAD_CONCURRENCY, MAX_DOWNLOAD_CONCURRENCY,
+};
+use history::HistoryStore;
+use queue::TranscriptionQueue;
+use scheduler::ResourceScheduler;
+use settings::{load_settings, SettingsManager};
+use statistics::StatisticsStore;
+use whisper::{
+    default_worker_count, kill_orphaned_sidecars, max_worker_count, JobRegistry, SidecarFlags,
+    WorkerPool,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    kill_orphaned_sidecars();
+
+    let settings = load_settings();
+    let worker_count = settings
+        .worker_count
+        .map(|n| n.clamp(1, max_worker_count()))
+        .unwrap_or_else(default_worker_count);
+    let download_concurrency = settings
+        .max_concurrent_downloads
+        .map(|n| n.clamp(1, MAX_DOWNLOAD_CONCURRENCY))
+        .unwrap_or(DEFAULT_DOWNLOAD_CONCURRENCY);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(WorkerPool::new(worker_count))
+        .manage(SidecarFlags::default())
+        .manage(JobRegistry::default())
+        .manage(ResourceScheduler::default())
+        .manage(DownloadRegistry::default())
+        .manage(DownloadQueue::new(download_concurrency))
+        .manage(TranscriptionQueue::default())
+        .manage(StatisticsStore::load())
+        .manage(HistoryStore::load())
+        .manage(SettingsManager::new(settings))
         .invoke_handler(tauri::generate_handler![
             list_models,
             download_model_command,
             get_model_path_command,
             delete_model,
             transcribe_audio,
+            get_worker_count,
+            set_worker_count,
+            kill_orphaned_processes,
+            evaluate_model,
+            get_supported_flags,
+            clear_transcription_cache,
+            get_accept_invalid_certs,
+            set_accept_invalid_certs,
+            get_model_readiness,
+            rescale_subtitle_timing,
+            get_keyword_notifications,
+            set_keyword_notifications,
+            analyze_audio_levels,
+            get_download_resume_info,
+            get_offline_mode,
+            set_offline_mode,
+            get_unbatched_output_events,
+            set_unbatched_output_events,
+            clip_transcript,
+            get_download_tuning,
+            set_download_tuning,
+            get_summary_command,
+            set_summary_command,
+            get_serialize_heavy_work,
+            set_serialize_heavy_work,
+            get_model_details,
+            render_subtitle_preview,
+            get_app_info,
+            generate_diagnostics,
+            get_job,
+            get_advanced_mode,
+            set_advanced_mode,
+            convert_transcript_file,
+            get_statistics,
+            reset_statistics,
+            get_settings,
+            update_settings,
+            cancel_download,
+            verify_model,
+            cancel_transcription,
+            import_model,
+            get_audio_info,
+            add_custom_model,
+            remove_custom_model,
+            enqueue_transcriptions,
+            get_queue,
+            remove_from_queue,
+            clear_queue,
+            detect_language,
+            get_system_info,
+            save_settings,
+            get_history,
+            clear_history,
+            transcribe_batch,
+            audio_info,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,3 @@
+pub mod validate;
+
+pub use validate::*;
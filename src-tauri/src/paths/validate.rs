@@ -0,0 +1,305 @@
+use crate::settings::SettingsManager;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_fs::FsExt;
+use tokio::fs;
+
+fn get_app_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.whisper-gui.app")
+}
+
+/// `canonicalize` returns verbatim paths on Windows (`\\?\C:\...`,
+/// `\\?\UNC\server\share\...`), which many tools, whisper-cli included,
+/// don't accept. Strips the prefix back to the ordinary form.
+pub fn normalize_path(path: PathBuf) -> PathBuf {
+    if !cfg!(windows) {
+        return path;
+    }
+
+    let text = path.to_string_lossy();
+    if let Some(rest) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", rest))
+    } else if let Some(rest) = text.strip_prefix(r"\\?\") {
+        // Only drive paths; other verbatim forms have no plain equivalent.
+        if rest.as_bytes().get(1) == Some(&b':') {
+            PathBuf::from(rest)
+        } else {
+            path
+        }
+    } else {
+        path
+    }
+}
+
+fn require_absolute(path: &str) -> Result<&Path, String> {
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return Err(format!("Path must be absolute: {}", path.display()));
+    }
+    Ok(path)
+}
+
+/// Resolves a path the frontend wants read: it must be absolute and point to
+/// an existing file once symlinks and `..` components are resolved.
+/// `what` names the file in error messages, e.g. "Audio file".
+pub async fn resolve_input_file(path: &str, what: &str) -> Result<PathBuf, String> {
+    let original = require_absolute(path)?;
+    let resolved = fs::canonicalize(original)
+        .await
+        .map_err(|_| format!("{} not found: {}", what, original.display()))?;
+
+    if !resolved.is_file() {
+        return Err(format!("{} is not a file: {}", what, original.display()));
+    }
+
+    Ok(normalize_path(resolved))
+}
+
+/// Whether the resolved `path` is one of `roots` or inside one. Roots are
+/// canonicalized first, so a symlinked root still matches; roots that don't
+/// exist match nothing.
+pub fn is_within_roots(path: &Path, roots: &[PathBuf]) -> bool {
+    roots
+        .iter()
+        .filter_map(|root| std::fs::canonicalize(root).ok())
+        .map(normalize_path)
+        .any(|root| path.starts_with(root))
+}
+
+/// Whether the user chose `path`, or a folder containing it, in a native
+/// file dialog or by dropping it on the window; the dialog and fs plugins add
+/// those to the fs scope. The webview can't extend the scope itself.
+pub fn is_user_picked(app: &AppHandle, path: &Path) -> bool {
+    app.try_fs_scope()
+        .is_some_and(|scope| scope.is_allowed(path))
+}
+
+/// Writes are limited to the app's data folder, the configured output
+/// folder (which only a picked folder can become), and picked paths.
+async fn check_write_scope(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let mut roots = vec![get_app_data_dir()];
+    if let Some(dir) = app.state::<SettingsManager>().get().await.output_dir {
+        roots.push(PathBuf::from(dir));
+    }
+
+    if is_user_picked(app, path) || is_within_roots(path, &roots) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Refusing to write outside the output and app folders: {}",
+            path.display()
+        ))
+    }
+}
+
+/// Resolves a path the frontend wants written. The parent directory must
+/// exist and is canonicalized; an existing target must be a regular file, so
+/// a symlink planted at the destination can't redirect the write elsewhere.
+pub async fn resolve_output_file(app: &AppHandle, path: &str) -> Result<PathBuf, String> {
+    let resolved = resolve_output_target(path).await?;
+    check_write_scope(app, &resolved).await?;
+    Ok(resolved)
+}
+
+async fn resolve_output_target(path: &str) -> Result<PathBuf, String> {
+    let original = require_absolute(path)?;
+    let file_name = original
+        .file_name()
+        .ok_or_else(|| format!("Output path has no file name: {}", original.display()))?;
+    let parent = original
+        .parent()
+        .ok_or_else(|| format!("Output path has no parent folder: {}", original.display()))?;
+
+    let parent = fs::canonicalize(parent)
+        .await
+        .map_err(|_| format!("Output folder not found: {}", parent.display()))?;
+    let resolved = parent.join(file_name);
+
+    if let Ok(metadata) = fs::symlink_metadata(&resolved).await {
+        if metadata.file_type().is_symlink() {
+            return Err(format!(
+                "Refusing to write through a symlink: {}",
+                resolved.display()
+            ));
+        }
+        if !metadata.is_file() {
+            return Err(format!("Output path is not a file: {}", resolved.display()));
+        }
+    }
+
+    Ok(normalize_path(resolved))
+}
+
+/// Resolves a folder the frontend wants files written into; it must already
+/// exist and be in the write scope.
+pub async fn resolve_output_dir(app: &AppHandle, path: &str) -> Result<PathBuf, String> {
+    let resolved = resolve_existing_dir(path).await?;
+    check_write_scope(app, &resolved).await?;
+    Ok(resolved)
+}
+
+/// The folder of the resolved `input`, for files written next to it such as
+/// transcripts and summaries. Picking a file only scopes the file, so its
+/// folder is allowed when the input itself was picked.
+pub async fn resolve_input_dir(app: &AppHandle, input: &Path) -> Result<PathBuf, String> {
+    let parent = input
+        .parent()
+        .ok_or_else(|| format!("Input has no parent folder: {}", input.display()))?;
+    let dir = resolve_existing_dir(&parent.to_string_lossy()).await?;
+    if !is_user_picked(app, input) {
+        check_write_scope(app, &dir).await?;
+    }
+    Ok(dir)
+}
+
+/// Resolves `file_name` in the folder of `input`, with the same checks as
+/// `resolve_input_dir` and `resolve_output_file`.
+pub async fn resolve_output_beside(
+    app: &AppHandle,
+    input: &Path,
+    file_name: &OsStr,
+) -> Result<PathBuf, String> {
+    let dir = resolve_input_dir(app, input).await?;
+    resolve_output_target(&dir.join(file_name).to_string_lossy()).await
+}
+
+async fn resolve_existing_dir(path: &str) -> Result<PathBuf, String> {
+    let original = require_absolute(path)?;
+    let resolved = fs::canonicalize(original)
+        .await
+        .map_err(|_| format!("Output folder not found: {}", original.display()))?;
+
+    if !resolved.is_dir() {
+        return Err(format!(
+            "Output path is not a folder: {}",
+            original.display()
+        ));
+    }
+
+    Ok(normalize_path(resolved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    #[tokio::test]
+    async fn rejects_relative_paths() {
+        let err = resolve_input_file("audio.wav", "Audio file")
+            .await
+            .unwrap_err();
+        assert_eq!(err, "Path must be absolute: audio.wav");
+        assert!(resolve_output_target("out/../out.srt").await.is_err());
+        assert!(resolve_existing_dir("out").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_parent_components() {
+        let dir = tempfile::tempdir().unwrap();
+        let audio_dir = dir.path().join("audio");
+        std::fs::create_dir(&audio_dir).unwrap();
+        std::fs::write(audio_dir.join("clip.wav"), b"RIFF").unwrap();
+        let real = normalize_path(std::fs::canonicalize(audio_dir.join("clip.wav")).unwrap());
+
+        let dotted = audio_dir.join("..").join("audio").join("clip.wav");
+        let resolved = resolve_input_file(&text(&dotted), "Audio file")
+            .await
+            .unwrap();
+        assert_eq!(resolved, real);
+    }
+
+    #[tokio::test]
+    async fn parent_components_cannot_leave_a_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("output");
+        std::fs::create_dir(&root).unwrap();
+        let roots = vec![root.clone()];
+
+        let inside = resolve_output_target(&text(&root.join("a.srt")))
+            .await
+            .unwrap();
+        assert!(is_within_roots(&inside, &roots));
+
+        let escaped = root.join("..").join("a.srt");
+        let outside = resolve_output_target(&text(&escaped)).await.unwrap();
+        assert!(!is_within_roots(&outside, &roots));
+    }
+
+    #[test]
+    fn missing_roots_match_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = normalize_path(std::fs::canonicalize(dir.path()).unwrap());
+        assert!(!is_within_roots(&path, &[dir.path().join("missing")]));
+        assert!(!is_within_roots(&path, &[]));
+    }
+
+    #[tokio::test]
+    async fn rejects_files_and_missing_folders_as_output_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, b"").unwrap();
+
+        assert!(resolve_existing_dir(&text(&file))
+            .await
+            .unwrap_err()
+            .starts_with("Output path is not a folder"));
+        assert!(resolve_existing_dir(&text(&dir.path().join("missing")))
+            .await
+            .unwrap_err()
+            .starts_with("Output folder not found"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn symlinked_root_matches_its_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real");
+        let link = dir.path().join("link");
+        std::fs::create_dir(&real).unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let resolved = resolve_output_target(&text(&real.join("a.srt")))
+            .await
+            .unwrap();
+        assert!(is_within_roots(&resolved, &[link]));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn symlinked_dir_inside_a_root_cannot_leave_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("output");
+        let elsewhere = dir.path().join("elsewhere");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::create_dir(&elsewhere).unwrap();
+        std::os::unix::fs::symlink(&elsewhere, root.join("escape")).unwrap();
+
+        let resolved = resolve_output_target(&text(&root.join("escape").join("a.srt")))
+            .await
+            .unwrap();
+        assert!(resolved.starts_with(std::fs::canonicalize(&elsewhere).unwrap()));
+        assert!(!is_within_roots(&resolved, &[root]));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn refuses_to_write_through_a_symlinked_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target.txt");
+        let link = dir.path().join("out.srt");
+        std::fs::write(&target, b"").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert!(resolve_output_target(&text(&link))
+            .await
+            .unwrap_err()
+            .starts_with("Refusing to write through a symlink"));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::subtitles::TranscriptFormat;
+use serde::Serialize;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+/// Where an item sits in the batch it was queued with.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchPosition {
+    pub id: String,
+    pub index: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueItem {
+    pub id: String,
+    pub audio_path: String,
+    pub model_name: String,
+    pub output_formats: Vec<TranscriptFormat>,
+    pub language: Option<String>,
+    pub status: QueueStatus,
+    /// Set while running, so the item can be stopped with
+    /// `cancel_transcription`.
+    pub job_id: Option<String>,
+    /// The transcript files written for a finished item.
+    pub output_files: Vec<String>,
+    pub error: Option<String>,
+    pub batch: Option<BatchPosition>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    items: Vec<QueueItem>,
+    worker_running: bool,
+}
+
+/// Files waiting to be transcribed one after another, plus finished ones
+/// until they are cleared.
+#[derive(Default)]
+pub struct TranscriptionQueue {
+    state: Mutex<QueueState>,
+}
+
+impl TranscriptionQueue {
+    pub fn items(&self) -> Vec<QueueItem> {
+        self.state.lock().unwrap().items.clone()
+    }
+
+    /// Adds items and reports whether a worker needs to be started for them.
+    pub fn push(&self, items: Vec<QueueItem>) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.items.extend(items);
+        !std::mem::replace(&mut state.worker_running, true)
+    }
+
+    /// Marks the next pending item as running. When none is left the worker
+    /// is marked as stopped in the same step, so a concurrent `push` starts
+    /// a new one.
+    pub fn start_next(&self) -> Option<QueueItem> {
+        let mut state = self.state.lock().unwrap();
+        match state
+            .items
+            .iter_mut()
+            .find(|item| item.status == QueueStatus::Pending)
+        {
+            Some(item) => {
+                item.status = QueueStatus::Running;
+                Some(item.clone())
+            }
+            None => {
+                state.worker_running = false;
+                None
+            }
+        }
+    }
+
+    pub fn set_job_id(&self, id: &str, job_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(item) = find_item(&mut state.items, id) {
+            item.job_id = Some(job_id.to_string());
+        }
+    }
+
+    pub fn finish(&self, id: &str, result: Result<Vec<String>, String>) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(item) = find_item(&mut state.items, id) {
+            match result {
+                Ok(output_files) => {
+                    item.status = QueueStatus::Done;
+                    item.output_files = output_files;
+                }
+                Err(err) => {
+                    item.status = QueueStatus::Failed;
+                    item.error = Some(err);
+                }
+            }
+        }
+    }
+
+    pub fn has_pending_in_batch(&self, batch_id: &str) -> bool {
+        self.state.lock().unwrap().items.iter().any(|item| {
+            item.status == QueueStatus::Pending
+                && item
+                    .batch
+                    .as_ref()
+                    .is_some_and(|batch| batch.id == batch_id)
+        })
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        let index = state
+            .items
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or_else(|| format!("Queue item '{}' not found", id))?;
+        if state.items[index].status == QueueStatus::Running {
+            return Err("The item is running; cancel its transcription instead".to_string());
+        }
+        state.items.remove(index);
+        Ok(())
+    }
+
+    /// Drops everything except the running item.
+    pub fn clear(&self) {
+        self.state
+            .lock()
+            .unwrap()
+            .items
+            .retain(|item| item.status == QueueStatus::Running);
+    }
+}
+
+fn find_item<'a>(items: &'a mut [QueueItem], id: &str) -> Option<&'a mut QueueItem> {
+    items.iter_mut().find(|item| item.id == id)
+}
@@ -0,0 +1,3 @@
+pub mod resources;
+
+pub use resources::*;
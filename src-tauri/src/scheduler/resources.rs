@@ -0,0 +1,204 @@
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkKind {
+    Download,
+    Transcription,
+}
+
+/// Counts of heavy work in flight. Downloads wait for running transcriptions
+/// to finish when heavy work is serialized; transcriptions always start.
+#[derive(Debug, Default)]
+pub struct ResourceState {
+    transcriptions: usize,
+    downloads: usize,
+}
+
+impl ResourceState {
+    pub fn can_start(&self, kind: WorkKind, serialize: bool) -> bool {
+        match kind {
+            WorkKind::Transcription => true,
+            WorkKind::Download => !serialize || self.transcriptions == 0,
+        }
+    }
+
+    pub fn start(&mut self, kind: WorkKind) {
+        match kind {
+            WorkKind::Transcription => self.transcriptions += 1,
+            WorkKind::Download => self.downloads += 1,
+        }
+    }
+
+    pub fn finish(&mut self, kind: WorkKind) {
+        match kind {
+            WorkKind::Transcription => self.transcriptions = self.transcriptions.saturating_sub(1),
+            WorkKind::Download => self.downloads = self.downloads.saturating_sub(1),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<ResourceState>,
+    changed: Notify,
+}
+
+#[derive(Default)]
+pub struct ResourceScheduler {
+    shared: Arc<Shared>,
+}
+
+/// Marks its work as finished when dropped.
+pub struct ResourceGuard {
+    kind: WorkKind,
+    shared: Arc<Shared>,
+}
+
+impl ResourceScheduler {
+    /// Waits until `kind` may start. `on_deferred` runs once if it has to
+    /// wait; `force` starts immediately regardless of other work.
+    pub async fn start<F>(
+        &self,
+        kind: WorkKind,
+        serialize: bool,
+        force: bool,
+        on_deferred: F,
+    ) -> ResourceGuard
+    where
+        F: FnOnce(),
+    {
+        let mut on_deferred = Some(on_deferred);
+
+        loop {
+            // Register for wakeups before checking, so a finish between the
+            // check and the await isn't missed.
+            let changed = self.shared.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            {
+                let mut state = self.shared.state.lock().unwrap();
+                if force || state.can_start(kind, serialize) {
+                    state.start(kind);
+                    return ResourceGuard {
+                        kind,
+                        shared: self.shared.clone(),
+                    };
+                }
+            }
+
+            if let Some(callback) = on_deferred.take() {
+                callback();
+            }
+            changed.await;
+        }
+    }
+}
+
+impl Drop for ResourceGuard {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().finish(self.kind);
+        self.shared.changed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn downloads_wait_for_transcriptions_only_when_serialized() {
+        let mut state = ResourceState::default();
+        assert!(state.can_start(WorkKind::Download, true));
+
+        state.start(WorkKind::Transcription);
+        assert!(!state.can_start(WorkKind::Download, true));
+        assert!(state.can_start(WorkKind::Download, false));
+
+        state.finish(WorkKind::Transcription);
+        assert!(state.can_start(WorkKind::Download, true));
+    }
+
+    #[test]
+    fn transcriptions_always_start() {
+        let mut state = ResourceState::default();
+        state.start(WorkKind::Download);
+        state.start(WorkKind::Transcription);
+        assert!(state.can_start(WorkKind::Transcription, true));
+    }
+
+    #[test]
+    fn downloads_wait_until_the_last_transcription_finishes() {
+        let mut state = ResourceState::default();
+        state.start(WorkKind::Transcription);
+        state.start(WorkKind::Transcription);
+
+        state.finish(WorkKind::Transcription);
+        assert!(!state.can_start(WorkKind::Download, true));
+        state.finish(WorkKind::Transcription);
+        assert!(state.can_start(WorkKind::Download, true));
+    }
+
+    #[test]
+    fn extra_finishes_do_not_underflow() {
+        let mut state = ResourceState::default();
+        state.finish(WorkKind::Transcription);
+        state.finish(WorkKind::Download);
+        state.start(WorkKind::Transcription);
+        assert!(!state.can_start(WorkKind::Download, true));
+    }
+
+    #[tokio::test]
+    async fn deferred_download_starts_once_the_transcription_ends() {
+        let scheduler = Arc::new(ResourceScheduler::default());
+        let transcription = scheduler
+            .start(WorkKind::Transcription, true, false, || {})
+            .await;
+
+        let deferrals = Arc::new(AtomicUsize::new(0));
+        let download = tokio::spawn({
+            let scheduler = scheduler.clone();
+            let deferrals = deferrals.clone();
+            async move {
+                let _guard = scheduler
+                    .start(WorkKind::Download, true, false, || {
+                        deferrals.fetch_add(1, Ordering::SeqCst);
+                    })
+                    .await;
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!download.is_finished());
+        assert_eq!(deferrals.load(Ordering::SeqCst), 1);
+
+        drop(transcription);
+        tokio::time::timeout(Duration::from_secs(5), download)
+            .await
+            .expect("download never started")
+            .unwrap();
+        assert_eq!(deferrals.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn forced_and_unserialized_downloads_start_immediately() {
+        let scheduler = ResourceScheduler::default();
+        let _transcription = scheduler
+            .start(WorkKind::Transcription, true, false, || {})
+            .await;
+
+        for (serialize, force) in [(true, true), (false, false)] {
+            let started = tokio::time::timeout(
+                Duration::from_secs(5),
+                scheduler.start(WorkKind::Download, serialize, force, || {
+                    panic!("should not be deferred")
+                }),
+            )
+            .await;
+            assert!(started.is_ok());
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use super::{get_settings_path, write_settings, Settings};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsChanged {
+    pub keys: Vec<String>,
+}
+
+/// Single owner of the settings. Every read and write goes through here so
+/// concurrent commands can't interleave read-modify-write cycles on the file.
+pub struct SettingsManager {
+    settings: RwLock<Settings>,
+    path: PathBuf,
+}
+
+impl SettingsManager {
+    pub fn new(settings: Settings) -> Self {
+        Self::with_path(settings, get_settings_path())
+    }
+
+    /// Saves to `path` instead of the usual settings file.
+    pub fn with_path(settings: Settings, path: PathBuf) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            path,
+        }
+    }
+
+    pub async fn get(&self) -> Settings {
+        self.settings.read().await.clone()
+    }
+
+    /// Applies `change` to the current settings and saves them. The write lock
+    /// is held until the file is written, so updates are applied one at a
+    /// time. Emits `settings-changed` with the top-level keys that changed.
+    pub async fn update<F>(&self, app: &AppHandle, change: F) -> Result<Settings, String>
+    where
+        F: FnOnce(&mut Settings) -> Result<(), String>,
+    {
+        let (updated, keys) = self.apply(change).await?;
+        if !keys.is_empty() {
+            let _ = app.emit("settings-changed", SettingsChanged { keys });
+        }
+        Ok(updated)
+    }
+
+    /// `update` without the event; returns the keys that changed.
+    async fn apply<F>(&self, change: F) -> Result<(Settings, Vec<String>), String>
+    where
+        F: FnOnce(&mut Settings) -> Result<(), String>,
+    {
+        let mut settings = self.settings.write().await;
+        let mut updated = settings.clone();
+        change(&mut updated)?;
+
+        let keys = changed_keys(&settings, &updated)?;
+        if !keys.is_empty() {
+            write_settings(&self.path, &updated).await?;
+            *settings = updated.clone();
+        }
+        Ok((updated, keys))
+    }
+
+    /// Merges the top-level keys of `patch` over the current settings.
+    /// `validate` runs on the merged result before anything is saved.
+    pub async fn patch<F>(
+        &self,
+        app: &AppHandle,
+        patch: Value,
+        validate: F,
+    ) -> Result<Settings, String>
+    where
+        F: FnOnce(&Settings, &Settings) -> Result<(), String>,
+    {
+        let Value::Object(patch) = patch else {
+            return Err("Settings patch must be an object".to_string());
+        };
+
+        self.update(app, |settings| merge_patch(settings, patch, validate))
+            .await
+    }
+}
+
+fn merge_patch<F>(
+    settings: &mut Settings,
+    patch: Map<String, Value>,
+    validate: F,
+) -> Result<(), String>
+where
+    F: FnOnce(&Settings, &Settings) -> Result<(), String>,
+{
+    let mut merged = serde_json::to_value(&*settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let fields = merged
+        .as_object_mut()
+        .ok_or("Settings did not serialize to an object")?;
+    for (key, value) in patch {
+        if !fields.contains_key(&key) {
+            return Err(format!("Unknown setting '{}'", key));
+        }
+        fields.insert(key, value);
+    }
+
+    let merged: Settings =
+        serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+    validate(settings, &merged)?;
+    *settings = merged;
+    Ok(())
+}
+
+fn changed_keys(before: &Settings, after: &Settings) -> Result<Vec<String>, String> {
+    let to_value = |s: &Settings| {
+        serde_json::to_value(s).map_err(|e| format!("Failed to serialize settings: {}", e))
+    };
+    let (Value::Object(before), Value::Object(after)) = (to_value(before)?, to_value(after)?)
+    else {
+        return Ok(Vec::new());
+    };
+
+    Ok(after
+        .into_iter()
+        .filter(|(key, value)| before.get(key) != Some(value))
+        .map(|(key, _)| key)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn patch_of(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("patch must be an object"),
+        }
+    }
+
+    async fn apply_patch(manager: &SettingsManager, patch: Value) -> Result<Settings, String> {
+        manager
+            .apply(|settings| merge_patch(settings, patch_of(patch), |_, _| Ok(())))
+            .await
+            .map(|(settings, _)| settings)
+    }
+
+    fn manager(dir: &tempfile::TempDir) -> SettingsManager {
+        SettingsManager::with_path(Settings::default(), dir.path().join("settings.json"))
+    }
+
+    #[tokio::test]
+    async fn concurrent_partial_updates_all_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = Arc::new(manager(&dir));
+        let patches = [
+            json!({ "offline_mode": true }),
+            json!({ "advanced_mode": true }),
+            json!({ "keyword_notifications": true }),
+            json!({ "worker_count": 3 }),
+            json!({ "default_model": "small" }),
+            json!({ "default_language": "de" }),
+            json!({ "threads": 2 }),
+            json!({ "max_concurrent_downloads": 4 }),
+        ];
+
+        let tasks: Vec<_> = patches
+            .into_iter()
+            .map(|patch| {
+                let manager = manager.clone();
+                tokio::spawn(async move { apply_patch(&manager, patch).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let settings = manager.get().await;
+        assert!(settings.offline_mode);
+        assert!(settings.advanced_mode);
+        assert!(settings.keyword_notifications);
+        assert_eq!(settings.worker_count, Some(3));
+        assert_eq!(settings.default_model, "small");
+        assert_eq!(settings.default_language.as_deref(), Some("de"));
+        assert_eq!(settings.threads, Some(2));
+        assert_eq!(settings.max_concurrent_downloads, Some(4));
+
+        let saved: Settings = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("settings.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(saved).unwrap(),
+            serde_json::to_value(settings).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_only_the_keys_that_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager(&dir);
+
+        let (_, keys) = manager
+            .apply(|settings| {
+                let patch = patch_of(json!({ "offline_mode": true, "advanced_mode": false }));
+                merge_patch(settings, patch, |_, _| Ok(()))
+            })
+            .await
+            .unwrap();
+        assert_eq!(keys, ["offline_mode"]);
+
+        let (_, keys) = manager
+            .apply(|settings| {
+                merge_patch(
+                    settings,
+                    patch_of(json!({ "offline_mode": true })),
+                    |_, _| Ok(()),
+                )
+            })
+            .await
+            .unwrap();
+        assert!(keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejected_patches_change_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager(&dir);
+
+        let unknown = apply_patch(&manager, json!({ "offline_mode": true, "nope": 1 })).await;
+        assert_eq!(unknown.unwrap_err(), "Unknown setting 'nope'");
+
+        let invalid = apply_patch(&manager, json!({ "worker_count": "many" })).await;
+        assert!(invalid.unwrap_err().starts_with("Invalid settings:"));
+
+        let vetoed = manager
+            .apply(|settings| {
+                merge_patch(
+                    settings,
+                    patch_of(json!({ "offline_mode": true })),
+                    |_, _| Err("no".to_string()),
+                )
+            })
+            .await;
+        assert_eq!(vetoed.unwrap_err(), "no");
+
+        assert!(!manager.get().await.offline_mode);
+        assert!(!dir.path().join("settings.json").exists());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod manager;
+pub mod store;
+
+pub use manager::*;
+pub use store::*;
@@ -0,0 +1,123 @@
+use crate::summary::SummaryCommand;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub worker_count: Option<usize>,
+    pub danger_accept_invalid_certs: bool,
+    pub keyword_notifications: bool,
+    pub offline_mode: bool,
+    /// Emits one `transcription-output` event per line for frontends that
+    /// predate `transcription-output-batch`.
+    pub unbatched_output_events: bool,
+    pub download_tuning: DownloadTuning,
+    /// Runs after each successful transcription when set.
+    pub summary_command: Option<SummaryCommand>,
+    /// Holds new downloads back while a transcription is running, since disk
+    /// and CPU contention slows both down.
+    pub serialize_heavy_work: bool,
+    /// Allows passing raw whisper-cli arguments with a transcription.
+    pub advanced_mode: bool,
+    /// How many models download at once. `None` uses the default of two.
+    pub max_concurrent_downloads: Option<usize>,
+    /// The frontend's choices from the last session, offered again on launch.
+    pub default_model: String,
+    pub default_format: String,
+    pub default_language: Option<String>,
+    pub threads: Option<u32>,
+    /// Where transcripts are written when none is picked, instead of next to
+    /// the audio. The frontend may write anywhere inside it.
+    pub output_dir: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            worker_count: None,
+            danger_accept_invalid_certs: false,
+            keyword_notifications: false,
+            offline_mode: false,
+            unbatched_output_events: false,
+            download_tuning: DownloadTuning::default(),
+            summary_command: None,
+            serialize_heavy_work: true,
+            advanced_mode: false,
+            max_concurrent_downloads: None,
+            default_model: "base".to_string(),
+            default_format: "txt".to_string(),
+            default_language: None,
+            threads: None,
+            output_dir: None,
+        }
+    }
+}
+
+/// Connection tuning for model downloads. The defaults favour a few large
+/// transfers from a CDN over many small requests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadTuning {
+    /// TCP keepalive probe interval. Keeps NAT and satellite links from
+    /// silently dropping a stalled connection, at the cost of a few extra
+    /// packets. `None` leaves it to the OS.
+    pub tcp_keepalive_secs: Option<u64>,
+    /// How long an idle connection is kept for reuse. Longer saves TLS
+    /// handshakes between downloads on high-latency links but holds sockets
+    /// open. `None` keeps idle connections indefinitely.
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// Lets HTTP/2 grow its flow-control window to match the link's
+    /// bandwidth-delay product. Helps long, fat links; slightly more memory
+    /// per connection.
+    pub http2_adaptive_window: bool,
+}
+
+impl Default for DownloadTuning {
+    fn default() -> Self {
+        Self {
+            tcp_keepalive_secs: Some(30),
+            pool_idle_timeout_secs: Some(90),
+            http2_adaptive_window: true,
+        }
+    }
+}
+
+pub fn get_settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.whisper-gui.app")
+        .join("settings.json")
+}
+
+/// Missing or unreadable settings fall back to defaults so a broken file
+/// never prevents the app from starting.
+pub fn load_settings() -> Settings {
+    std::fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub async fn write_settings(path: &Path, settings: &Settings) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json)
+        .await
+        .map_err(|e| format!("Failed to write settings: {}", e))?;
+
+    fs::rename(&temp_path, &path)
+        .await
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    Ok(())
+}
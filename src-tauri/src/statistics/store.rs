@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayStats {
+    pub transcriptions: u64,
+    pub audio_ms: u64,
+    pub processing_ms: u64,
+    pub downloads: u64,
+    pub bytes_downloaded: u64,
+    /// Transcriptions per model name.
+    pub models: BTreeMap<String, u64>,
+}
+
+impl DayStats {
+    fn add(&mut self, other: &DayStats) {
+        self.transcriptions += other.transcriptions;
+        self.audio_ms += other.audio_ms;
+        self.processing_ms += other.processing_ms;
+        self.downloads += other.downloads;
+        self.bytes_downloaded += other.bytes_downloaded;
+        for (model, count) in &other.models {
+            *self.models.entry(model.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Counters bucketed by UTC day (`YYYY-MM-DD`), so any period can be
+/// summed without keeping per-job records.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    pub days: BTreeMap<String, DayStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyStatistics {
+    pub date: String,
+    #[serde(flatten)]
+    pub stats: DayStats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatisticsSummary {
+    pub period: String,
+    #[serde(flatten)]
+    pub totals: DayStats,
+    /// Processing time divided by audio length; below 1 is faster than
+    /// realtime.
+    pub average_realtime_factor: Option<f64>,
+    pub daily: Vec<DailyStatistics>,
+}
+
+pub fn get_statistics_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.whisper-gui.app")
+        .join("statistics.json")
+}
+
+/// Days since the Unix epoch to a proleptic Gregorian `YYYY-MM-DD`.
+fn civil_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn today() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| (d.as_secs() / 86_400) as i64)
+        .unwrap_or(0)
+}
+
+/// Statistics kept in memory and written through on every update, so
+/// queries never rescan anything.
+pub struct StatisticsStore {
+    stats: Mutex<Statistics>,
+}
+
+impl StatisticsStore {
+    /// Unreadable statistics start over rather than blocking startup.
+    pub fn load() -> Self {
+        let stats = std::fs::read_to_string(get_statistics_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            stats: Mutex::new(stats),
+        }
+    }
+
+    async fn update<F>(&self, apply: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut DayStats),
+    {
+        let mut stats = self.stats.lock().await;
+        apply(stats.days.entry(civil_date(today())).or_default());
+        write_statistics(&stats).await
+    }
+
+    pub async fn record_transcription(
+        &self,
+        model: &str,
+        audio_ms: u64,
+        processing_ms: u64,
+    ) -> Result<(), String> {
+        self.update(|day| {
+            day.transcriptions += 1;
+            day.audio_ms += audio_ms;
+            day.processing_ms += processing_ms;
+            *day.models.entry(model.to_string()).or_default() += 1;
+        })
+        .await
+    }
+
+    pub async fn record_download(&self, bytes: u64) -> Result<(), String> {
+        self.update(|day| {
+            day.downloads += 1;
+            day.bytes_downloaded += bytes;
+        })
+        .await
+    }
+
+    /// `days` limits the summary to that many most recent days, today
+    /// included; `None` covers everything.
+    pub async fn summary(&self, period: &str, days: Option<i64>) -> StatisticsSummary {
+        let stats = self.stats.lock().await;
+        let first_day = days.map(|days| civil_date(today() - days + 1));
+
+        let mut totals = DayStats::default();
+        let mut daily = Vec::new();
+        for (date, day) in &stats.days {
+            if first_day.as_ref().is_some_and(|first| date < first) {
+                continue;
+            }
+            totals.add(day);
+            daily.push(DailyStatistics {
+                date: date.clone(),
+                stats: day.clone(),
+            });
+        }
+
+        let average_realtime_factor =
+            (totals.audio_ms > 0).then(|| totals.processing_ms as f64 / totals.audio_ms as f64);
+
+        StatisticsSummary {
+            period: period.to_string(),
+            totals,
+            average_realtime_factor,
+            daily,
+        }
+    }
+
+    pub async fn reset(&self) -> Result<(), String> {
+        let mut stats = self.stats.lock().await;
+        *stats = Statistics::default();
+        write_statistics(&stats).await
+    }
+}
+
+async fn write_statistics(stats: &Statistics) -> Result<(), String> {
+    let path = get_statistics_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create statistics directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string(stats)
+        .map_err(|e| format!("Failed to serialize statistics: {}", e))?;
+
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json)
+        .await
+        .map_err(|e| format!("Failed to write statistics: {}", e))?;
+
+    fs::rename(&temp_path, &path)
+        .await
+        .map_err(|e| format!("Failed to save statistics: {}", e))?;
+
+    Ok(())
+}
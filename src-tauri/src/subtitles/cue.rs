@@ -0,0 +1,339 @@
+use super::{
+    format_timestamp, group_paragraphs, parse_cue_timing, TimestampStyle, DEFAULT_PARAGRAPH_GAP_MS,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    Srt,
+    Vtt,
+    /// whisper-cli `-oj` output.
+    Json,
+    /// Cue text only; can be written but not read back.
+    Txt,
+}
+
+impl TranscriptFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            "json" => Some(Self::Json),
+            "txt" => Some(Self::Txt),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::parse(path.extension()?.to_str()?)
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Json => "json",
+            Self::Txt => "txt",
+        }
+    }
+}
+
+pub fn parse_transcript(contents: &str, format: TranscriptFormat) -> Result<Vec<Cue>, String> {
+    match format {
+        TranscriptFormat::Srt | TranscriptFormat::Vtt => parse_cues(contents),
+        TranscriptFormat::Json => parse_whisper_json(contents),
+        TranscriptFormat::Txt => Err("Plain text transcripts have no timings to read".to_string()),
+    }
+}
+
+pub fn format_transcript(cues: &[Cue], format: TranscriptFormat) -> String {
+    match format {
+        TranscriptFormat::Srt => format_srt(cues),
+        TranscriptFormat::Vtt => format_vtt(cues),
+        TranscriptFormat::Json => format_whisper_json(cues),
+        TranscriptFormat::Txt => format_txt(cues, DEFAULT_PARAGRAPH_GAP_MS),
+    }
+}
+
+fn is_cue_number(line: &str) -> bool {
+    !line.is_empty() && line.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Parses the cues of an SRT or VTT file. Cue numbers, the VTT header and
+/// NOTE/STYLE/REGION blocks are skipped; a missing blank line between two
+/// cues is tolerated.
+pub fn parse_cues(contents: &str) -> Result<Vec<Cue>, String> {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+
+    let mut cues = Vec::new();
+    let mut current: Option<Cue> = None;
+    let mut skipping_block = false;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            cues.extend(current.take());
+            skipping_block = false;
+            continue;
+        }
+        if skipping_block {
+            continue;
+        }
+
+        if line.contains("-->") {
+            let timing = parse_cue_timing(line)
+                .ok_or_else(|| format!("Invalid cue timing on line {}: {}", index + 1, line))?;
+            if timing.end_ms < timing.start_ms {
+                return Err(format!(
+                    "Cue ends before it starts on line {}: {}",
+                    index + 1,
+                    line
+                ));
+            }
+
+            if let Some(mut previous) = current.take() {
+                // The next cue's number ended up in the previous cue's text.
+                if let Some((rest, last)) = previous.text.rsplit_once('\n') {
+                    if is_cue_number(last) {
+                        previous.text = rest.to_string();
+                    }
+                } else if is_cue_number(&previous.text) {
+                    previous.text.clear();
+                }
+                cues.push(previous);
+            }
+
+            current = Some(Cue {
+                start_ms: timing.start_ms,
+                end_ms: timing.end_ms,
+                text: String::new(),
+            });
+            continue;
+        }
+
+        match current.as_mut() {
+            Some(cue) => {
+                if !cue.text.is_empty() {
+                    cue.text.push('\n');
+                }
+                cue.text.push_str(line);
+            }
+            None => {
+                if line.starts_with("WEBVTT") || is_cue_number(line) {
+                    continue;
+                }
+                if line.starts_with("NOTE") || line == "STYLE" || line == "REGION" {
+                    skipping_block = true;
+                    continue;
+                }
+                // A VTT cue identifier; the timing line follows.
+            }
+        }
+    }
+    cues.extend(current);
+
+    Ok(cues)
+}
+
+pub fn format_srt(cues: &[Cue]) -> String {
+    let mut result = String::new();
+    for (index, cue) in cues.iter().enumerate() {
+        result.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(cue.start_ms, TimestampStyle::Srt),
+            format_timestamp(cue.end_ms, TimestampStyle::Srt),
+            cue.text
+        ));
+    }
+    result
+}
+
+pub fn format_vtt(cues: &[Cue]) -> String {
+    let style = TimestampStyle::Vtt { hours: true };
+    let mut result = String::from("WEBVTT\n\n");
+    for cue in cues {
+        result.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(cue.start_ms, style),
+            format_timestamp(cue.end_ms, style),
+            cue.text
+        ));
+    }
+    result
+}
+
+/// Plain text with cues joined into paragraphs; see `group_paragraphs`.
+pub fn format_txt(cues: &[Cue], paragraph_gap_ms: u64) -> String {
+    group_paragraphs(cues, paragraph_gap_ms)
+        .iter()
+        .map(|paragraph| {
+            let lines: Vec<&str> = paragraph
+                .iter()
+                .flat_map(|cue| cue.text.lines())
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect();
+            lines.join(" ") + "\n"
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn parse_whisper_json(contents: &str) -> Result<Vec<Cue>, String> {
+    let value: Value =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid transcript JSON: {}", e))?;
+
+    let segments = value
+        .get("transcription")
+        .and_then(Value::as_array)
+        .ok_or("Transcript JSON has no \"transcription\" array")?;
+
+    segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            let offset = |key: &str| {
+                segment
+                    .pointer(&format!("/offsets/{}", key))
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| format!("Segment {} is missing offsets.{}", index, key))
+            };
+            Ok(Cue {
+                start_ms: offset("from")?,
+                end_ms: offset("to")?,
+                text: segment
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn format_whisper_json(cues: &[Cue]) -> String {
+    let segments: Vec<Value> = cues
+        .iter()
+        .map(|cue| {
+            json!({
+                "timestamps": {
+                    "from": format_timestamp(cue.start_ms, TimestampStyle::Srt),
+                    "to": format_timestamp(cue.end_ms, TimestampStyle::Srt),
+                },
+                "offsets": { "from": cue.start_ms, "to": cue.end_ms },
+                "text": cue.text,
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&json!({ "transcription": segments })).unwrap_or_default()
+}
+
+/// Keeps the cues overlapping `start_ms..end_ms`, trimming partially
+/// overlapping ones to the range. With `rebase`, the clip starts at zero.
+pub fn clip_cues(cues: &[Cue], start_ms: u64, end_ms: u64, rebase: bool) -> Vec<Cue> {
+    let offset = if rebase { start_ms } else { 0 };
+
+    cues.iter()
+        .filter(|cue| cue.end_ms > start_ms && cue.start_ms < end_ms)
+        .map(|cue| Cue {
+            start_ms: cue.start_ms.max(start_ms) - offset,
+            end_ms: cue.end_ms.min(end_ms) - offset,
+            text: cue.text.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(start_ms: u64, end_ms: u64, text: &str) -> Cue {
+        Cue {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    const SRT: &str = "1\n00:00:01,000 --> 00:00:02,500\nHello\n\n2\n00:00:03,000 --> 00:01:04,250\nTwo\nlines\n\n";
+
+    #[test]
+    fn srt_round_trips() {
+        let cues = parse_cues(SRT).unwrap();
+        assert_eq!(
+            cues,
+            [cue(1000, 2500, "Hello"), cue(3000, 64_250, "Two\nlines")]
+        );
+        assert_eq!(format_srt(&cues), SRT);
+    }
+
+    #[test]
+    fn strips_a_byte_order_mark() {
+        let with_bom = format!("\u{feff}{}", SRT);
+        assert_eq!(parse_cues(&with_bom).unwrap(), parse_cues(SRT).unwrap());
+    }
+
+    #[test]
+    fn tolerates_missing_blank_lines_between_cues() {
+        let contents =
+            "1\n00:00:01,000 --> 00:00:02,000\nHello\n2\n00:00:03,000 --> 00:00:04,000\nWorld\n";
+        assert_eq!(
+            parse_cues(contents).unwrap(),
+            [cue(1000, 2000, "Hello"), cue(3000, 4000, "World")]
+        );
+    }
+
+    #[test]
+    fn misnumbered_cues_are_renumbered_on_output() {
+        let contents =
+            "7\n00:00:01,000 --> 00:00:02,000\nA\n\n3\n00:00:03,000 --> 00:00:04,000\nB\n\n";
+        let cues = parse_cues(contents).unwrap();
+        assert_eq!(cues, [cue(1000, 2000, "A"), cue(3000, 4000, "B")]);
+        assert!(format_srt(&cues).starts_with("1\n00:00:01,000"));
+        assert!(format_srt(&cues).contains("\n\n2\n00:00:03,000"));
+    }
+
+    #[test]
+    fn accepts_dot_and_comma_milliseconds() {
+        let comma = "1\n00:00:01,250 --> 00:00:02,500\nHi\n";
+        let dot = "1\n00:00:01.250 --> 00:00:02.500\nHi\n";
+        assert_eq!(parse_cues(comma).unwrap(), parse_cues(dot).unwrap());
+        assert_eq!(
+            format_srt(&parse_cues(dot).unwrap()),
+            "1\n00:00:01,250 --> 00:00:02,500\nHi\n\n"
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_an_invalid_timing() {
+        let contents =
+            "1\n00:00:01,000 --> 00:00:02,000\nHello\n\n2\n00:00:03 --> 00:00:04,000\nWorld\n";
+        assert_eq!(
+            parse_cues(contents).unwrap_err(),
+            "Invalid cue timing on line 6: 00:00:03 --> 00:00:04,000"
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_a_cue_ending_before_it_starts() {
+        let contents = "\u{feff}1\n00:00:05,000 --> 00:00:04,000\nBackwards\n";
+        assert_eq!(
+            parse_cues(contents).unwrap_err(),
+            "Cue ends before it starts on line 2: 00:00:05,000 --> 00:00:04,000"
+        );
+    }
+}
@@ -0,0 +1,11 @@
+pub mod cue;
+pub mod paragraphs;
+pub mod preview;
+pub mod rescale;
+pub mod timestamp;
+
+pub use cue::*;
+pub use paragraphs::*;
+pub use preview::*;
+pub use rescale::*;
+pub use timestamp::*;
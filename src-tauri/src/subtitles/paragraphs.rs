@@ -0,0 +1,125 @@
+use super::Cue;
+
+/// A pause this long always starts a new paragraph.
+pub const DEFAULT_PARAGRAPH_GAP_MS: u64 = 2000;
+
+const SENTENCE_ENDINGS: &[char] = &['.', '!', '?', '…', '。', '！', '？', '؟', '।'];
+
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end()
+        .trim_end_matches(['"', '\'', '”', '’', ')', ']', '»'])
+        .ends_with(SENTENCE_ENDINGS)
+}
+
+/// Groups consecutive cues into paragraphs. A pause of at least `gap_ms`
+/// always breaks; a cue ending a sentence breaks on half that pause. Text
+/// without sentence punctuation is therefore split on pauses alone.
+pub fn group_paragraphs(cues: &[Cue], gap_ms: u64) -> Vec<&[Cue]> {
+    let mut paragraphs = Vec::new();
+    let mut start = 0;
+
+    for i in 1..cues.len() {
+        let previous = &cues[i - 1];
+        let gap = cues[i].start_ms.saturating_sub(previous.end_ms);
+        let sentence_break = ends_sentence(&previous.text) && gap >= gap_ms / 2;
+
+        if gap >= gap_ms || sentence_break {
+            paragraphs.push(&cues[start..i]);
+            start = i;
+        }
+    }
+    if start < cues.len() {
+        paragraphs.push(&cues[start..]);
+    }
+
+    paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subtitles::format_txt;
+
+    fn cue(start_ms: u64, end_ms: u64, text: &str) -> Cue {
+        Cue {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    fn texts(paragraphs: &[&[Cue]]) -> Vec<Vec<String>> {
+        paragraphs
+            .iter()
+            .map(|p| p.iter().map(|c| c.text.clone()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn empty_input_has_no_paragraphs() {
+        assert!(group_paragraphs(&[], DEFAULT_PARAGRAPH_GAP_MS).is_empty());
+    }
+
+    #[test]
+    fn long_pause_always_breaks() {
+        let cues = [cue(0, 1000, "and then"), cue(3000, 4000, "we left")];
+        assert_eq!(
+            texts(&group_paragraphs(&cues, 2000)),
+            [vec!["and then"], vec!["we left"]]
+        );
+    }
+
+    #[test]
+    fn sentence_end_breaks_on_half_the_pause() {
+        let cues = [
+            cue(0, 1000, "It rained."),
+            cue(2000, 3000, "We stayed in"),
+            cue(3500, 4000, "all day."),
+        ];
+        assert_eq!(
+            texts(&group_paragraphs(&cues, 2000)),
+            [vec!["It rained."], vec!["We stayed in", "all day."]]
+        );
+    }
+
+    #[test]
+    fn sentence_end_without_a_pause_does_not_break() {
+        let cues = [cue(0, 1000, "Yes."), cue(1200, 2000, "No.")];
+        assert_eq!(group_paragraphs(&cues, 2000).len(), 1);
+    }
+
+    #[test]
+    fn closing_quotes_and_other_scripts_end_sentences() {
+        assert!(ends_sentence("He said \"stop.\""));
+        assert!(ends_sentence("(really?) "));
+        assert!(ends_sentence("谢谢。"));
+        assert!(ends_sentence("हाँ।"));
+        assert!(!ends_sentence("well,"));
+    }
+
+    #[test]
+    fn text_without_punctuation_splits_on_pauses_alone() {
+        let cues = [
+            cue(0, 1000, "so we went"),
+            cue(1900, 2500, "down to the river"),
+            cue(5000, 6000, "it was cold"),
+        ];
+        assert_eq!(
+            texts(&group_paragraphs(&cues, 2000)),
+            [vec!["so we went", "down to the river"], vec!["it was cold"]]
+        );
+    }
+
+    #[test]
+    fn format_txt_joins_paragraph_lines() {
+        let cues = [
+            cue(0, 1000, " It rained. "),
+            cue(2000, 3000, "We stayed\nin"),
+            cue(3100, 4000, "all day."),
+        ];
+        assert_eq!(
+            format_txt(&cues, 2000),
+            "It rained.\n\nWe stayed in all day.\n"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::Cue;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SubtitleStyle {
+    pub max_chars_per_line: usize,
+    pub max_lines: usize,
+    /// Reading speed above which a cue is flagged as too fast.
+    pub max_chars_per_second: f64,
+    /// Cues shorter than this are flagged as flashing by too quickly.
+    pub min_duration_ms: u64,
+}
+
+impl Default for SubtitleStyle {
+    // Common broadcast guidelines: two lines of 42 characters, 17 cps.
+    fn default() -> Self {
+        Self {
+            max_chars_per_line: 42,
+            max_lines: 2,
+            max_chars_per_second: 17.0,
+            min_duration_ms: 833,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadingSpeedFlag {
+    TooFast,
+    TooShort,
+    TooManyLines,
+    LineTooLong,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CuePreview {
+    pub index: usize,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub lines: Vec<String>,
+    pub chars_per_second: f64,
+    pub flags: Vec<ReadingSpeedFlag>,
+}
+
+/// Greedy word wrap. A single word longer than `max_chars` gets a line of
+/// its own rather than being split.
+pub fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for word in text.split_whitespace() {
+        let word_len = word.chars().count();
+        if current_len > 0 && current_len + 1 + word_len > max_chars {
+            lines.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if current_len > 0 {
+            current.push(' ');
+            current_len += 1;
+        }
+        current.push_str(word);
+        current_len += word_len;
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+pub fn preview_cues(cues: &[Cue], style: &SubtitleStyle) -> Vec<CuePreview> {
+    let max_chars = style.max_chars_per_line.max(1);
+
+    cues.iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            let lines = wrap_text(&cue.text, max_chars);
+            let duration_ms = cue.end_ms.saturating_sub(cue.start_ms);
+            let chars: usize = lines.iter().map(|l| l.chars().count()).sum();
+            let chars_per_second = chars as f64 * 1000.0 / duration_ms.max(1) as f64;
+
+            let mut flags = Vec::new();
+            if chars_per_second > style.max_chars_per_second {
+                flags.push(ReadingSpeedFlag::TooFast);
+            }
+            if duration_ms < style.min_duration_ms {
+                flags.push(ReadingSpeedFlag::TooShort);
+            }
+            if lines.len() > style.max_lines {
+                flags.push(ReadingSpeedFlag::TooManyLines);
+            }
+            if lines.iter().any(|l| l.chars().count() > max_chars) {
+                flags.push(ReadingSpeedFlag::LineTooLong);
+            }
+
+            CuePreview {
+                index,
+                start_ms: cue.start_ms,
+                end_ms: cue.end_ms,
+                lines,
+                chars_per_second,
+                flags,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,85 @@
+use super::{format_timestamp, parse_cue_timing};
+
+/// Applies `map` to both timestamps of every SRT/VTT cue timing line,
+/// leaving cue text, numbering, VTT cue settings and line endings untouched.
+pub fn map_cue_timings<F>(contents: &str, map: F) -> Result<String, String>
+where
+    F: Fn(u64) -> u64,
+{
+    let mut result = String::with_capacity(contents.len());
+
+    for (index, line) in contents.split_inclusive('\n').enumerate() {
+        if !line.contains("-->") {
+            result.push_str(line);
+            continue;
+        }
+
+        let timing = parse_cue_timing(line).ok_or_else(|| {
+            format!(
+                "Invalid cue timing on line {}: {}",
+                index + 1,
+                line.trim_end()
+            )
+        })?;
+
+        result.push_str(&format_timestamp(map(timing.start_ms), timing.start_style));
+        result.push_str(" --> ");
+        result.push_str(&format_timestamp(map(timing.end_ms), timing.end_style));
+        result.push_str(timing.settings);
+    }
+
+    Ok(result)
+}
+
+/// Converting 24 fps film to 25 fps video plays it 4% faster, so every
+/// timestamp shrinks by `source_fps / target_fps`.
+pub fn rescale_timings(contents: &str, source_fps: f64, target_fps: f64) -> Result<String, String> {
+    let ratio = source_fps / target_fps;
+    map_cue_timings(contents, |ms| (ms as f64 * ratio).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRT: &str = "1\r\n00:00:25,000 --> 00:00:27,500\r\nHello\r\n\r\n2\r\n01:00:00,000 --> 01:00:02,000\r\nBye\r\n";
+
+    const VTT: &str = "WEBVTT\n\n00:25.000 --> 00:27.500 align:start line:0\nHello\n\nNOTE kept as is\n\n01:00:00.000 --> 01:00:02.000\nBye\n";
+
+    #[test]
+    fn identity_keeps_files_byte_for_byte() {
+        assert_eq!(map_cue_timings(SRT, |ms| ms).unwrap(), SRT);
+        assert_eq!(map_cue_timings(VTT, |ms| ms).unwrap(), VTT);
+        assert_eq!(rescale_timings(VTT, 25.0, 25.0).unwrap(), VTT);
+    }
+
+    #[test]
+    fn film_to_pal_shrinks_srt_timings() {
+        assert_eq!(
+            rescale_timings(SRT, 24.0, 25.0).unwrap(),
+            "1\r\n00:00:24,000 --> 00:00:26,400\r\nHello\r\n\r\n2\r\n00:57:36,000 --> 00:57:37,920\r\nBye\r\n"
+        );
+    }
+
+    #[test]
+    fn film_to_pal_keeps_vtt_styles_and_settings() {
+        assert_eq!(
+            rescale_timings(VTT, 24.0, 25.0).unwrap(),
+            "WEBVTT\n\n00:24.000 --> 00:26.400 align:start line:0\nHello\n\nNOTE kept as is\n\n00:57:36.000 --> 00:57:37.920\nBye\n"
+        );
+    }
+
+    #[test]
+    fn scaling_back_restores_the_original() {
+        let pal = rescale_timings(SRT, 24.0, 25.0).unwrap();
+        assert_eq!(rescale_timings(&pal, 25.0, 24.0).unwrap(), SRT);
+        let pal = rescale_timings(VTT, 24.0, 25.0).unwrap();
+        assert_eq!(rescale_timings(&pal, 25.0, 24.0).unwrap(), VTT);
+    }
+
+    #[test]
+    fn reports_the_line_of_a_bad_timing() {
+        let err = map_cue_timings("1\n00:00:01,000 --> later\nHi\n", |ms| ms).unwrap_err();
+        assert_eq!(err, "Invalid cue timing on line 2: 00:00:01,000 --> later");
+    }
+}
@@ -0,0 +1,181 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampStyle {
+    /// `HH:MM:SS,mmm`
+    Srt,
+    /// `HH:MM:SS.mmm`, or `MM:SS.mmm` when `hours` is false.
+    Vtt { hours: bool },
+}
+
+pub fn parse_timestamp(text: &str) -> Option<(u64, TimestampStyle)> {
+    let text = text.trim();
+    let (clock, millis, comma) = match text.rsplit_once(',') {
+        Some((clock, millis)) => (clock, millis, true),
+        None => {
+            let (clock, millis) = text.rsplit_once('.')?;
+            (clock, millis, false)
+        }
+    };
+
+    if millis.len() != 3 || !millis.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let millis: u64 = millis.parse().ok()?;
+
+    let parts: Vec<&str> = clock.split(':').collect();
+    let numbers: Vec<u64> = parts
+        .iter()
+        .map(|p| {
+            if p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit()) {
+                None
+            } else {
+                p.parse().ok()
+            }
+        })
+        .collect::<Option<_>>()?;
+
+    let (hours, minutes, seconds) = match numbers.as_slice() {
+        [h, m, s] => (*h, *m, *s),
+        [m, s] => (0, *m, *s),
+        _ => return None,
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    let style = if comma {
+        TimestampStyle::Srt
+    } else {
+        TimestampStyle::Vtt {
+            hours: numbers.len() == 3,
+        }
+    };
+
+    Some((
+        ((hours * 60 + minutes) * 60 + seconds) * 1000 + millis,
+        style,
+    ))
+}
+
+pub fn format_timestamp(ms: u64, style: TimestampStyle) -> String {
+    let hours = ms / 3_600_000;
+    let minutes = (ms / 60_000) % 60;
+    let seconds = (ms / 1000) % 60;
+    let millis = ms % 1000;
+
+    match style {
+        TimestampStyle::Srt => format!("{:02}:{:02}:{:02},{:03}", hours, minutes, seconds, millis),
+        // Hours can only be left out when they're zero.
+        TimestampStyle::Vtt { hours: false } if hours == 0 => {
+            format!("{:02}:{:02}.{:03}", minutes, seconds, millis)
+        }
+        TimestampStyle::Vtt { .. } => {
+            format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, seconds, millis)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CueTiming<'a> {
+    pub start_ms: u64,
+    pub start_style: TimestampStyle,
+    pub end_ms: u64,
+    pub end_style: TimestampStyle,
+    /// Anything after the end timestamp, such as VTT cue settings and the
+    /// line ending.
+    pub settings: &'a str,
+}
+
+/// Parses an SRT/VTT cue timing line such as
+/// `00:00:01,000 --> 00:00:04,500` or `00:01.000 --> 00:04.500 align:start`.
+pub fn parse_cue_timing(line: &str) -> Option<CueTiming<'_>> {
+    let (start, rest) = line.split_once("-->")?;
+
+    let rest = rest.trim_start();
+    let end_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let (end, settings) = rest.split_at(end_len);
+
+    let (start_ms, start_style) = parse_timestamp(start)?;
+    let (end_ms, end_style) = parse_timestamp(end)?;
+
+    Some(CueTiming {
+        start_ms,
+        start_style,
+        end_ms,
+        end_style,
+        settings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_srt_and_both_vtt_styles() {
+        assert_eq!(
+            parse_timestamp("01:02:03,004"),
+            Some((3_723_004, TimestampStyle::Srt))
+        );
+        assert_eq!(
+            parse_timestamp(" 01:02:03.004 "),
+            Some((3_723_004, TimestampStyle::Vtt { hours: true }))
+        );
+        assert_eq!(
+            parse_timestamp("02:03.004"),
+            Some((123_004, TimestampStyle::Vtt { hours: false }))
+        );
+        // VTT allows more than two digits of hours.
+        assert_eq!(
+            parse_timestamp("100:00:00.000"),
+            Some((360_000_000, TimestampStyle::Vtt { hours: true }))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_timestamps() {
+        for text in [
+            "",
+            "00:00:01",
+            "00:00:01.5",
+            "00:00:01.5000",
+            "00:60:00.000",
+            "00:00:60,000",
+            "1:2:3:4.000",
+            "aa:00.000",
+            "00::01.000",
+            "-1:00.000",
+        ] {
+            assert_eq!(parse_timestamp(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        for text in ["00:00:00,000", "12:34:56,789", "00:00:05.250", "59:59.999"] {
+            let (ms, style) = parse_timestamp(text).unwrap();
+            assert_eq!(format_timestamp(ms, style), text);
+        }
+    }
+
+    #[test]
+    fn short_vtt_style_gains_hours_when_needed() {
+        let style = TimestampStyle::Vtt { hours: false };
+        assert_eq!(format_timestamp(3_599_999, style), "59:59.999");
+        assert_eq!(format_timestamp(3_600_000, style), "01:00:00.000");
+    }
+
+    #[test]
+    fn parses_cue_timings_with_settings_and_line_endings() {
+        let timing = parse_cue_timing("00:01.000 --> 00:04.500 align:start line:0\r\n").unwrap();
+        assert_eq!((timing.start_ms, timing.end_ms), (1000, 4500));
+        assert_eq!(timing.start_style, TimestampStyle::Vtt { hours: false });
+        assert_eq!(timing.settings, " align:start line:0\r\n");
+
+        let timing = parse_cue_timing("00:00:01,000 --> 00:00:04,500\n").unwrap();
+        assert_eq!(timing.end_style, TimestampStyle::Srt);
+        assert_eq!(timing.settings, "\n");
+
+        assert!(parse_cue_timing("00:00:01,000 00:00:04,500").is_none());
+        assert!(parse_cue_timing("00:00:01,000 --> soon").is_none());
+    }
+}
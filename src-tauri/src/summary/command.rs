@@ -0,0 +1,117 @@
+use crate::whisper::kill_process;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::ShellExt;
+
+/// A user-configured summarizer. The program is run directly, never through
+/// a shell, with `args` passed verbatim; the transcript only ever reaches it
+/// on stdin, so transcript text can't inject arguments or shell syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryCommand {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl SummaryCommand {
+    pub fn validate(&self) -> Result<(), String> {
+        let program = Path::new(&self.program);
+        if !program.is_absolute() {
+            return Err("Summary command must be an absolute path to a program".to_string());
+        }
+        if !program.is_file() {
+            return Err(format!("Summary program not found: {}", program.display()));
+        }
+        if self.args.iter().any(|arg| arg.contains('\0')) {
+            return Err("Summary command arguments can't contain NUL bytes".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Generous enough for a local model working through an hour of speech; a
+/// summarizer still running after this is assumed to be stuck.
+const SUMMARY_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+pub fn summary_path(audio_path: &Path) -> PathBuf {
+    audio_path.with_extension("summary.txt")
+}
+
+/// Pipes `transcript` to the summary command and returns what it prints.
+pub async fn run_summary_command(
+    app: &AppHandle,
+    command: &SummaryCommand,
+    transcript: &str,
+) -> Result<String, String> {
+    command.validate()?;
+
+    let (mut rx, mut child) = app
+        .shell()
+        .command(&command.program)
+        .args(&command.args)
+        .set_raw_out(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start summary command: {}", e))?;
+
+    // Write from a blocking thread while output is drained below, so a tool
+    // that streams its answer before reading all of stdin can't deadlock.
+    // Dropping the child closes stdin, so only its pid is kept for the kill.
+    let pid = child.pid();
+    let input = transcript.as_bytes().to_vec();
+    let writer = tokio::task::spawn_blocking(move || child.write(&input));
+
+    let collect = async {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut exit_code = None;
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(bytes) => stdout.extend(bytes),
+                CommandEvent::Stderr(bytes) => stderr.extend(bytes),
+                CommandEvent::Terminated(payload) => exit_code = payload.code,
+                CommandEvent::Error(err) => return Err(format!("Summary command failed: {}", err)),
+                _ => {}
+            }
+        }
+        Ok((stdout, stderr, exit_code))
+    };
+    let (stdout, stderr, exit_code) = match tokio::time::timeout(SUMMARY_TIMEOUT, collect).await {
+        Ok(result) => result?,
+        Err(_) => {
+            // Also unblocks the writer, whose pipe breaks.
+            kill_process(pid);
+            return Err(format!(
+                "Summary command timed out after {} minutes",
+                SUMMARY_TIMEOUT.as_secs() / 60
+            ));
+        }
+    };
+
+    // A tool may legitimately exit before reading all of its input, so a
+    // broken pipe only matters if the exit code says it failed.
+    let write_result = writer.await;
+
+    if exit_code != Some(0) {
+        if let Ok(Err(e)) = write_result {
+            return Err(format!(
+                "Failed to send transcript to summary command: {}",
+                e
+            ));
+        }
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Err(match stderr.trim() {
+            "" => format!("Summary command exited with code {:?}", exit_code),
+            message => format!("Summary command failed: {}", message),
+        });
+    }
+
+    let summary = String::from_utf8_lossy(&stdout).trim().to_string();
+    if summary.is_empty() {
+        return Err("Summary command produced no output".to_string());
+    }
+
+    Ok(summary)
+}
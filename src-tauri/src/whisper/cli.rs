@@ -1,55 +1,235 @@
-use std::path::Path;
+use super::{
+    lower_process_priority, output_flag, parse_detected_language, DetectedLanguage, JobHandle,
+    ProcessPriority,
+};
+use crate::subtitles::{format_timestamp, parse_timestamp, TimestampStyle, TranscriptFormat};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
 use tauri::AppHandle;
 use tauri_plugin_shell::ShellExt;
-use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
 use tokio::sync::mpsc;
 
 #[derive(Debug, Clone)]
 pub enum TranscriptionEvent {
+    /// Stdout lines that aren't segments, such as model loading banners.
     Stdout(String),
     Stderr(String),
-    Completed(String),
+    Segment(TranscriptSegment),
+    Completed {
+        output: String,
+        segments: Vec<TranscriptSegment>,
+    },
     Error(String),
+    /// whisper-cli's guess when the language is left on auto.
+    LanguageDetected(DetectedLanguage),
+    /// How far into the audio whisper-cli is, from segment end times.
+    Progress {
+        percent: f64,
+        processed_ms: u64,
+        total_ms: u64,
+    },
 }
 
-pub async fn run_transcription(
-    app: AppHandle,
-    audio_path: &Path,
-    model_path: &Path,
-    output_format: &str,
-    language: Option<&str>,
-) -> Result<mpsc::Receiver<TranscriptionEvent>, String> {
-    let (tx, rx) = mpsc::channel(100);
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptSegment {
+    pub index: usize,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+impl TranscriptSegment {
+    /// The stdout line whisper-cli printed for this segment.
+    pub fn to_line(&self) -> String {
+        let style = TimestampStyle::Vtt { hours: true };
+        format!(
+            "[{} --> {}]  {}",
+            format_timestamp(self.start_ms, style),
+            format_timestamp(self.end_ms, style),
+            self.text
+        )
+    }
+}
+
+/// Timestamped segments in whisper-cli's stdout. Empty with `-nt`, which
+/// prints text without timestamps.
+pub fn parse_segments(output: &str) -> Vec<TranscriptSegment> {
+    output
+        .lines()
+        .filter_map(parse_segment_line)
+        .filter(|(_, _, text)| !is_blank_segment(text))
+        .enumerate()
+        .map(|(index, (start_ms, end_ms, text))| TranscriptSegment {
+            index,
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        })
+        .collect()
+}
 
+/// Splits a whisper-cli stdout line of the form
+/// `[00:00:01.000 --> 00:00:04.500]  text` into its times and text.
+pub fn parse_segment_line(line: &str) -> Option<(u64, u64, &str)> {
+    let (stamp, text) = line.trim().strip_prefix('[')?.split_once(']')?;
+    let (start, end) = stamp.split_once("-->")?;
+    let (start_ms, _) = parse_timestamp(start)?;
+    let (end_ms, _) = parse_timestamp(end)?;
+    Some((start_ms, end_ms, text.trim()))
+}
+
+/// whisper-cli prints silence as an empty or `[BLANK_AUDIO]` segment.
+pub fn is_blank_segment(text: &str) -> bool {
+    text.is_empty() || text == "[BLANK_AUDIO]"
+}
+
+/// Drops whisper-cli's `[start --> end]` prefixes and joins the remaining
+/// text into a single line.
+pub fn transcript_text(output: &str) -> String {
+    output
+        .lines()
+        .map(|line| match parse_segment_line(line) {
+            Some((_, _, text)) => text,
+            None => line.trim(),
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WhisperOptions {
+    /// Files whisper-cli writes besides printing to stdout.
+    pub output_formats: Vec<TranscriptFormat>,
+    /// Path the files are written to, minus the extension. whisper-cli
+    /// falls back to the input path, which may be a temporary conversion.
+    pub output_base: Option<PathBuf>,
+    /// Writes the full JSON output, which has per-token timings.
+    pub word_timestamps: bool,
+    pub language: Option<String>,
+    pub grammar_file: Option<PathBuf>,
+    pub grammar_rule: Option<String>,
+    pub grammar_penalty: Option<f32>,
+    /// Translates the speech to English; `language` is still the source.
+    pub translate: bool,
+    /// `None` leaves whisper-cli's defaults.
+    pub threads: Option<u32>,
+    pub processors: Option<u32>,
+    /// Applied to the process after spawning; not a whisper-cli flag.
+    pub priority: ProcessPriority,
+    /// Validated user arguments, appended after everything the app sets.
+    pub extra_args: Vec<String>,
+}
+
+pub fn build_args(audio_path: &Path, model_path: &Path, options: &WhisperOptions) -> Vec<String> {
     let mut args = vec![
         "-m".to_string(),
         model_path.to_string_lossy().to_string(),
         "-f".to_string(),
         audio_path.to_string_lossy().to_string(),
-        "-o".to_string(),
-        output_format.to_string(),
     ];
 
-    if let Some(lang) = language {
-        if lang != "auto" {
+    if options.word_timestamps {
+        args.push("-ojf".to_string());
+    }
+    if !options.output_formats.is_empty() || options.word_timestamps {
+        for format in &options.output_formats {
+            args.push(output_flag(*format).to_string());
+        }
+        if let Some(base) = &options.output_base {
+            args.push("-of".to_string());
+            args.push(base.to_string_lossy().to_string());
+        }
+    }
+
+    match options.language.as_deref() {
+        Some("auto") | None => {
+            // whisper-cli assumes English without `-l`, which would turn
+            // translation into a no-op; make it detect the source instead.
+            if options.translate {
+                args.push("-l".to_string());
+                args.push("auto".to_string());
+            }
+        }
+        Some(lang) => {
             args.push("-l".to_string());
             args.push(lang.to_string());
         }
     }
 
-    let shell = app.shell();
-    let command = shell
+    if options.translate {
+        args.push("-tr".to_string());
+    }
+
+    if let Some(threads) = options.threads {
+        args.push("-t".to_string());
+        args.push(threads.to_string());
+    }
+    if let Some(processors) = options.processors {
+        args.push("-p".to_string());
+        args.push(processors.to_string());
+    }
+
+    if let Some(grammar_file) = &options.grammar_file {
+        args.push("--grammar".to_string());
+        args.push(grammar_file.to_string_lossy().to_string());
+        if let Some(rule) = &options.grammar_rule {
+            args.push("--grammar-rule".to_string());
+            args.push(rule.clone());
+        }
+        if let Some(penalty) = options.grammar_penalty {
+            args.push("--grammar-penalty".to_string());
+            args.push(penalty.to_string());
+        }
+    }
+
+    args.extend(options.extra_args.iter().cloned());
+
+    args
+}
+
+pub fn spawn_sidecar(
+    app: &AppHandle,
+    args: &[String],
+) -> Result<(mpsc::Receiver<CommandEvent>, CommandChild), String> {
+    app.shell()
         .sidecar("binaries/whisper-cli")
         .map_err(|e| format!("Failed to create sidecar command: {}", e))?
-        .args(&args);
-
-    let (mut rx_cmd, _child) = command
+        .args(args)
         .spawn()
-        .map_err(|e| format!("Failed to spawn whisper-cli: {}", e))?;
+        .map_err(|e| format!("Failed to spawn whisper-cli: {}", e))
+}
+
+pub async fn run_transcription(
+    app: AppHandle,
+    audio_path: &Path,
+    model_path: &Path,
+    options: &WhisperOptions,
+    job: &JobHandle,
+    audio_duration_ms: Option<u64>,
+) -> Result<mpsc::Receiver<TranscriptionEvent>, String> {
+    let (tx, rx) = mpsc::channel(100);
+
+    let args = build_args(audio_path, model_path, options);
+    let (mut rx_cmd, child) = spawn_sidecar(&app, &args)?;
+
+    if options.priority == ProcessPriority::Low {
+        // Not worth failing the job over; it just runs at normal priority.
+        if let Err(err) = lower_process_priority(child.pid()) {
+            eprintln!("{}", err);
+        }
+    }
+
+    job.attach_child(child);
+    let cancelled = job.cancel_flag();
 
     let tx_clone = tx.clone();
     tokio::spawn(async move {
         let mut full_output = String::new();
+        let mut segments = Vec::new();
 
         while let Some(event) = rx_cmd.recv().await {
             match event {
@@ -57,16 +237,53 @@ pub async fn run_transcription(
                     let line_str = String::from_utf8_lossy(&line).to_string();
                     full_output.push_str(&line_str);
                     full_output.push('\n');
-                    let _ = tx_clone.send(TranscriptionEvent::Stdout(line_str)).await;
+                    let Some((start_ms, end_ms, text)) = parse_segment_line(&line_str) else {
+                        let _ = tx_clone.send(TranscriptionEvent::Stdout(line_str)).await;
+                        continue;
+                    };
+
+                    if !is_blank_segment(text) {
+                        let segment = TranscriptSegment {
+                            index: segments.len(),
+                            start_ms,
+                            end_ms,
+                            text: text.to_string(),
+                        };
+                        segments.push(segment.clone());
+                        let _ = tx_clone.send(TranscriptionEvent::Segment(segment)).await;
+                    }
+
+                    if let Some(total_ms) = audio_duration_ms.filter(|&d| d > 0) {
+                        let processed_ms = end_ms.min(total_ms);
+                        let _ = tx_clone
+                            .send(TranscriptionEvent::Progress {
+                                percent: processed_ms as f64 / total_ms as f64 * 100.0,
+                                processed_ms,
+                                total_ms,
+                            })
+                            .await;
+                    }
                 }
                 CommandEvent::Stderr(line) => {
                     let line_str = String::from_utf8_lossy(&line).to_string();
+                    if let Some(detected) = parse_detected_language(&line_str) {
+                        let _ = tx_clone
+                            .send(TranscriptionEvent::LanguageDetected(detected))
+                            .await;
+                    }
                     let _ = tx_clone.send(TranscriptionEvent::Stderr(line_str)).await;
                 }
                 CommandEvent::Terminated(payload) => {
-                    if payload.code == Some(0) {
+                    if cancelled.load(Ordering::SeqCst) {
                         let _ = tx_clone
-                            .send(TranscriptionEvent::Completed(full_output.clone()))
+                            .send(TranscriptionEvent::Error("Cancelled by user".to_string()))
+                            .await;
+                    } else if payload.code == Some(0) {
+                        let _ = tx_clone
+                            .send(TranscriptionEvent::Completed {
+                                output: std::mem::take(&mut full_output),
+                                segments: std::mem::take(&mut segments),
+                            })
                             .await;
                     } else {
                         let _ = tx_clone
@@ -76,12 +293,208 @@ pub async fn run_transcription(
                             )))
                             .await;
                     }
-                    break;
+                    return;
                 }
                 _ => {}
             }
         }
+
+        // The event stream can close without a Terminated event when the
+        // process is killed; the job still has to finish.
+        let message = if cancelled.load(Ordering::SeqCst) {
+            "Cancelled by user"
+        } else {
+            "whisper-cli stopped without reporting an exit code"
+        };
+        let _ = tx_clone
+            .send(TranscriptionEvent::Error(message.to_string()))
+            .await;
     });
 
     Ok(rx)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_segment_lines() {
+        assert_eq!(
+            parse_segment_line("[00:00:01.000 --> 00:00:04.500]   Hello there."),
+            Some((1000, 4500, "Hello there."))
+        );
+        assert_eq!(
+            parse_segment_line("  [01:02:03.004 --> 01:02:05.000]  Late\r"),
+            Some((3_723_004, 3_725_000, "Late"))
+        );
+        assert_eq!(
+            parse_segment_line("[00:00:00.000 --> 00:00:02.000]"),
+            Some((0, 2000, ""))
+        );
+    }
+
+    #[test]
+    fn rejects_lines_that_are_not_segments() {
+        assert_eq!(
+            parse_segment_line("whisper_init_from_file: loading model"),
+            None
+        );
+        assert_eq!(parse_segment_line("[BLANK_AUDIO]"), None);
+        assert_eq!(parse_segment_line("[00:00:01.000]  no end"), None);
+        assert_eq!(
+            parse_segment_line("[00:00:01 --> 00:00:02]  no millis"),
+            None
+        );
+        assert_eq!(parse_segment_line(""), None);
+    }
+
+    #[test]
+    fn parse_segments_skips_blank_audio_and_renumbers() {
+        let output = "\
+whisper_print_timings: load time = 10 ms
+[00:00:00.000 --> 00:00:02.000]  [BLANK_AUDIO]
+[00:00:02.000 --> 00:00:05.000]   First line.
+[00:00:05.000 --> 00:00:06.000]
+[00:00:06.000 --> 00:00:09.250]   Second line.
+";
+        let segments = parse_segments(output);
+        let summary: Vec<_> = segments
+            .iter()
+            .map(|s| (s.index, s.start_ms, s.end_ms, s.text.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (0, 2000, 5000, "First line."),
+                (1, 6000, 9250, "Second line.")
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_segments_is_empty_without_timestamps() {
+        assert!(parse_segments(" Hello there.\n General Kenobi.\n").is_empty());
+    }
+
+    #[test]
+    fn segment_to_line_round_trips() {
+        let segment = TranscriptSegment {
+            index: 0,
+            start_ms: 61_500,
+            end_ms: 3_600_000,
+            text: "Hi".to_string(),
+        };
+        assert_eq!(segment.to_line(), "[00:01:01.500 --> 01:00:00.000]  Hi");
+        assert_eq!(
+            parse_segment_line(&segment.to_line()),
+            Some((61_500, 3_600_000, "Hi"))
+        );
+    }
+
+    #[test]
+    fn build_args_defaults_to_model_and_input() {
+        let args = build_args(
+            Path::new("in.wav"),
+            Path::new("ggml-base.bin"),
+            &WhisperOptions::default(),
+        );
+        assert_eq!(args, strings(&["-m", "ggml-base.bin", "-f", "in.wav"]));
+    }
+
+    #[test]
+    fn build_args_sets_output_files_and_base() {
+        let options = WhisperOptions {
+            output_formats: vec![TranscriptFormat::Srt, TranscriptFormat::Txt],
+            output_base: Some(PathBuf::from("out/talk")),
+            word_timestamps: true,
+            ..Default::default()
+        };
+        let args = build_args(Path::new("in.wav"), Path::new("m.bin"), &options);
+        assert_eq!(
+            args,
+            strings(&["-m", "m.bin", "-f", "in.wav", "-ojf", "-osrt", "-otxt", "-of", "out/talk"])
+        );
+    }
+
+    #[test]
+    fn build_args_ignores_output_base_without_files() {
+        let options = WhisperOptions {
+            output_base: Some(PathBuf::from("out/talk")),
+            ..Default::default()
+        };
+        let args = build_args(Path::new("in.wav"), Path::new("m.bin"), &options);
+        assert!(!args.contains(&"-of".to_string()), "{:?}", args);
+    }
+
+    #[test]
+    fn build_args_translation_detects_the_source_language() {
+        let options = WhisperOptions {
+            translate: true,
+            ..Default::default()
+        };
+        let args = build_args(Path::new("in.wav"), Path::new("m.bin"), &options);
+        assert_eq!(&args[4..], strings(&["-l", "auto", "-tr"]));
+
+        let options = WhisperOptions {
+            language: Some("de".to_string()),
+            translate: true,
+            ..Default::default()
+        };
+        let args = build_args(Path::new("in.wav"), Path::new("m.bin"), &options);
+        assert_eq!(&args[4..], strings(&["-l", "de", "-tr"]));
+
+        let options = WhisperOptions {
+            language: Some("auto".to_string()),
+            ..Default::default()
+        };
+        let args = build_args(Path::new("in.wav"), Path::new("m.bin"), &options);
+        assert_eq!(args.len(), 4, "{:?}", args);
+    }
+
+    #[test]
+    fn build_args_appends_extra_args_last() {
+        let options = WhisperOptions {
+            threads: Some(4),
+            processors: Some(2),
+            grammar_file: Some(PathBuf::from("g.gbnf")),
+            grammar_rule: Some("root".to_string()),
+            grammar_penalty: Some(50.0),
+            extra_args: strings(&["--beam-size", "5"]),
+            ..Default::default()
+        };
+        let args = build_args(Path::new("in.wav"), Path::new("m.bin"), &options);
+        assert_eq!(
+            &args[4..],
+            strings(&[
+                "-t",
+                "4",
+                "-p",
+                "2",
+                "--grammar",
+                "g.gbnf",
+                "--grammar-rule",
+                "root",
+                "--grammar-penalty",
+                "50",
+                "--beam-size",
+                "5",
+            ])
+        );
+    }
+
+    #[test]
+    fn grammar_rule_and_penalty_need_a_grammar_file() {
+        let options = WhisperOptions {
+            grammar_rule: Some("root".to_string()),
+            grammar_penalty: Some(50.0),
+            ..Default::default()
+        };
+        let args = build_args(Path::new("in.wav"), Path::new("m.bin"), &options);
+        assert_eq!(args.len(), 4, "{:?}", args);
+    }
+}
@@ -0,0 +1,29 @@
+use super::{run_transcription, JobHandle, TranscriptionEvent, WhisperOptions};
+use crate::audio::prepare_for_whisper;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Runs one transcription to the end without streaming events and returns
+/// whisper-cli's stdout.
+pub async fn collect_transcription(
+    app: AppHandle,
+    audio_path: &Path,
+    model_path: &Path,
+    options: &WhisperOptions,
+    job: &JobHandle,
+) -> Result<String, String> {
+    let converted = prepare_for_whisper(&app, audio_path).await?;
+    let input_path = converted.as_ref().map_or(audio_path, |file| file.path());
+
+    let mut rx = run_transcription(app, input_path, model_path, options, job, None).await?;
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            TranscriptionEvent::Completed { output, .. } => return Ok(output),
+            TranscriptionEvent::Error(err) => return Err(err),
+            _ => {}
+        }
+    }
+
+    Err("Transcription ended without a result".to_string())
+}
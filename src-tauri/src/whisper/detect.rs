@@ -0,0 +1,112 @@
+use super::{spawn_sidecar, JobHandle};
+use serde::Serialize;
+use std::path::Path;
+use tauri::AppHandle;
+use tauri_plugin_shell::process::CommandEvent;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedLanguage {
+    pub language: String,
+    pub confidence: f64,
+}
+
+/// Parses whisper-cli's `auto-detected language: de (p = 0.97)` line.
+pub fn parse_detected_language(line: &str) -> Option<DetectedLanguage> {
+    let (_, rest) = line.split_once("detected language:")?;
+    let (language, rest) = rest.trim().split_once(char::is_whitespace)?;
+    let confidence = rest
+        .trim()
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .split_once('=')?
+        .1
+        .trim()
+        .parse()
+        .ok()?;
+
+    Some(DetectedLanguage {
+        language: language.to_string(),
+        confidence,
+    })
+}
+
+/// Runs whisper-cli's `--detect-language` pass and stops the process as soon
+/// as the result is printed.
+pub async fn detect_language_with_sidecar(
+    app: &AppHandle,
+    audio_path: &Path,
+    model_path: &Path,
+    job: &JobHandle,
+) -> Result<DetectedLanguage, String> {
+    let args = vec![
+        "-m".to_string(),
+        model_path.to_string_lossy().to_string(),
+        "-f".to_string(),
+        audio_path.to_string_lossy().to_string(),
+        "-l".to_string(),
+        "auto".to_string(),
+        "--detect-language".to_string(),
+    ];
+    let (mut rx, child) = spawn_sidecar(app, &args)?;
+    job.attach_child(child);
+
+    while let Some(event) = rx.recv().await {
+        let line = match event {
+            CommandEvent::Stdout(line) | CommandEvent::Stderr(line) => line,
+            CommandEvent::Terminated(payload) => {
+                if job.is_cancelled() {
+                    return Err("Cancelled by user".to_string());
+                }
+                return Err(format!(
+                    "whisper-cli exited without detecting a language (code {:?})",
+                    payload.code
+                ));
+            }
+            _ => continue,
+        };
+
+        if let Some(detected) = parse_detected_language(&String::from_utf8_lossy(&line)) {
+            job.kill_child();
+            return Ok(detected);
+        }
+    }
+
+    Err("whisper-cli exited without detecting a language".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_whisper_cli_detection_line() {
+        let detected = parse_detected_language(
+            "whisper_full_with_state: auto-detected language: de (p = 0.97)",
+        )
+        .unwrap();
+        assert_eq!(detected.language, "de");
+        assert_eq!(detected.confidence, 0.97);
+    }
+
+    #[test]
+    fn tolerates_trailing_whitespace_and_missing_spaces() {
+        let detected =
+            parse_detected_language("auto-detected language: en (p=0.512345)\n").unwrap();
+        assert_eq!(detected.language, "en");
+        assert_eq!(detected.confidence, 0.512345);
+    }
+
+    #[test]
+    fn ignores_other_and_malformed_lines() {
+        for line in [
+            "",
+            "whisper_init_from_file_with_params_no_state: loading model",
+            "auto-detected language: de",
+            "auto-detected language: de p = 0.97",
+            "auto-detected language: de (p = high)",
+            "auto-detected language: (p = 0.97)",
+        ] {
+            assert!(parse_detected_language(line).is_none(), "{:?}", line);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use super::FlagInfo;
+
+/// Flags the app sets itself; letting extra args repeat them would silently
+/// change which model, input or output file is used.
+const MANAGED_FLAGS: &[&str] = &["-m", "--model", "-f", "--file", "-of", "--output-file"];
+
+fn flag_name(arg: &str) -> Option<&str> {
+    let name = arg.split_once('=').map_or(arg, |(name, _)| name);
+    let body = name.strip_prefix("--").or_else(|| name.strip_prefix('-'))?;
+
+    let mut chars = body.chars();
+    let starts_ok = chars.next().is_some_and(|c| c.is_ascii_alphabetic());
+    if starts_ok && chars.all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+fn lookup<'a>(supported: &'a [FlagInfo], name: &str) -> Option<&'a FlagInfo> {
+    supported
+        .iter()
+        .find(|f| f.flag == name || f.short.as_deref() == Some(name))
+}
+
+/// The flag names in `args`, skipping their values. whisper-cli takes the
+/// argument after a flag that needs a value as that value whatever it looks
+/// like, and any other bare argument as another input file.
+fn flag_names<'a>(args: &'a [String], supported: &[FlagInfo]) -> Result<Vec<&'a str>, String> {
+    let mut names = Vec::new();
+    let mut args = args.iter().peekable();
+
+    while let Some(arg) = args.next() {
+        let Some(name) = flag_name(arg) else {
+            return Err(format!(
+                "Extra argument must be a flag or the value of one: {}",
+                arg
+            ));
+        };
+        if MANAGED_FLAGS.contains(&name) {
+            return Err(format!(
+                "{} is set by the app and can't be passed as an extra argument",
+                name
+            ));
+        }
+        names.push(name);
+        if arg.contains('=') {
+            continue;
+        }
+
+        match lookup(supported, name) {
+            Some(info) if info.takes_value => {
+                if args.next().is_none() {
+                    return Err(format!("{} needs a value", name));
+                }
+            }
+            Some(_) => {}
+            // `--help` may not list every flag, so one value may follow an
+            // unknown one; whisper-cli refuses flags it really doesn't know.
+            None => {
+                args.next_if(|next| flag_name(next).is_none());
+            }
+        }
+    }
+
+    Ok(names)
+}
+
+/// Checks user-supplied whisper-cli arguments against the sidecar's flags.
+/// Every flag must look like `-x`, `--long` or `--long=value`, and only flags
+/// that take a value may be followed by one (e.g. `-t 4`,
+/// `--temperature -0.5`).
+pub fn validate_extra_args(args: &[String], supported: &[FlagInfo]) -> Result<(), String> {
+    if let Some(arg) = args.iter().find(|arg| arg.chars().any(char::is_control)) {
+        return Err(format!(
+            "Extra argument contains control characters: {:?}",
+            arg
+        ));
+    }
+    flag_names(args, supported).map(|_| ())
+}
+
+/// Flags in `args` that the sidecar's `--help` doesn't list. Expects args
+/// that passed `validate_extra_args`.
+pub fn unknown_extra_flags(args: &[String], supported: &[FlagInfo]) -> Vec<String> {
+    flag_names(args, supported)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| lookup(supported, name).is_none())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::baseline_flags;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn validate(list: &[&str]) -> Result<(), String> {
+        validate_extra_args(&args(list), &baseline_flags())
+    }
+
+    #[test]
+    fn accepts_flags_with_and_without_values() {
+        assert!(validate(&[]).is_ok());
+        assert!(validate(&["-t", "4", "-nf", "--beam-size", "5", "-sow"]).is_ok());
+        assert!(validate(&["--prompt", "Names: Ana, Bo"]).is_ok());
+        assert!(validate(&["--max-len=40", "-np"]).is_ok());
+    }
+
+    #[test]
+    fn rejects_managed_flags_in_every_form() {
+        for flag in [
+            &["-m", "other.bin"][..],
+            &["--model", "other.bin"],
+            &["--model=other.bin"],
+            &["-f", "other.wav"],
+            &["--file=other.wav"],
+            &["-of", "out"],
+            &["--output-file", "out"],
+        ] {
+            let err = validate(flag).unwrap_err();
+            assert!(err.contains("is set by the app"), "{:?}: {}", flag, err);
+        }
+    }
+
+    #[test]
+    fn rejects_bare_arguments_after_flags_without_values() {
+        let err = validate(&["-np", "/other/audio.wav"]).unwrap_err();
+        assert_eq!(
+            err,
+            "Extra argument must be a flag or the value of one: /other/audio.wav"
+        );
+        assert!(validate(&["/other/audio.wav"]).is_err());
+        assert!(validate(&["-t", "4", "8"]).is_err());
+        assert!(validate(&["--max-len=40", "/other/audio.wav"]).is_err());
+    }
+
+    #[test]
+    fn values_may_look_like_flags_or_negative_numbers() {
+        let mut supported = baseline_flags();
+        supported.push(FlagInfo {
+            flag: "--temperature".to_string(),
+            short: Some("-tp".to_string()),
+            description: String::new(),
+            takes_value: true,
+        });
+        assert!(validate_extra_args(&args(&["-tp", "-0.5"]), &supported).is_ok());
+        // whisper-cli reads `-m` here as the prompt, not as a model.
+        assert!(validate_extra_args(&args(&["--prompt", "-m"]), &supported).is_ok());
+        assert_eq!(
+            validate_extra_args(&args(&["-nf", "--temperature"]), &supported),
+            Err("--temperature needs a value".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_flags_may_take_one_value() {
+        assert!(validate(&["--vad", "--vad-threshold", "0.6"]).is_ok());
+        assert!(validate(&["--vad-threshold", "0.6", "0.7"]).is_err());
+        assert!(validate(&["-x", "-0.5"]).is_ok());
+    }
+
+    #[test]
+    fn rejects_control_characters_in_values() {
+        assert!(validate(&["--prompt", "line\nbreak"]).is_err());
+    }
+
+    #[test]
+    fn reports_only_unlisted_flags() {
+        let unknown = unknown_extra_flags(
+            &args(&["-t", "4", "--vad", "--prompt", "--not-a-flag", "-x=1"]),
+            &baseline_flags(),
+        );
+        assert_eq!(unknown, ["--vad", "-x"]);
+    }
+}
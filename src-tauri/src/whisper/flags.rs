@@ -0,0 +1,353 @@
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::OnceCell;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlagInfo {
+    pub flag: String,
+    pub short: Option<String>,
+    pub description: String,
+    pub takes_value: bool,
+}
+
+/// Flags every whisper-cli build we ship supports, used when `--help`
+/// can't be run or parsed.
+const BASELINE_FLAGS: &[(&str, &str, bool, &str)] = &[
+    (
+        "-t",
+        "--threads",
+        true,
+        "number of threads to use during computation",
+    ),
+    (
+        "-p",
+        "--processors",
+        true,
+        "number of processors to use during computation",
+    ),
+    ("-ot", "--offset-t", true, "time offset in milliseconds"),
+    ("-on", "--offset-n", true, "segment index offset"),
+    (
+        "-d",
+        "--duration",
+        true,
+        "duration of audio to process in milliseconds",
+    ),
+    (
+        "-ml",
+        "--max-len",
+        true,
+        "maximum segment length in characters",
+    ),
+    (
+        "-sow",
+        "--split-on-word",
+        false,
+        "split on word rather than on token",
+    ),
+    (
+        "-bo",
+        "--best-of",
+        true,
+        "number of best candidates to keep",
+    ),
+    ("-bs", "--beam-size", true, "beam size for beam search"),
+    (
+        "-tr",
+        "--translate",
+        false,
+        "translate from source language to english",
+    ),
+    (
+        "-nf",
+        "--no-fallback",
+        false,
+        "do not use temperature fallback while decoding",
+    ),
+    (
+        "-otxt",
+        "--output-txt",
+        false,
+        "output result in a text file",
+    ),
+    (
+        "-ovtt",
+        "--output-vtt",
+        false,
+        "output result in a vtt file",
+    ),
+    (
+        "-osrt",
+        "--output-srt",
+        false,
+        "output result in a srt file",
+    ),
+    (
+        "-olrc",
+        "--output-lrc",
+        false,
+        "output result in a lrc file",
+    ),
+    (
+        "-ocsv",
+        "--output-csv",
+        false,
+        "output result in a CSV file",
+    ),
+    (
+        "-oj",
+        "--output-json",
+        false,
+        "output result in a JSON file",
+    ),
+    (
+        "-ojf",
+        "--output-json-full",
+        false,
+        "include more information in the JSON file",
+    ),
+    (
+        "-of",
+        "--output-file",
+        true,
+        "output file path (without file extension)",
+    ),
+    (
+        "-np",
+        "--no-prints",
+        false,
+        "do not print anything other than the results",
+    ),
+    ("-pp", "--print-progress", false, "print progress"),
+    ("-nt", "--no-timestamps", false, "do not print timestamps"),
+    (
+        "-l",
+        "--language",
+        true,
+        "spoken language ('auto' for auto-detect)",
+    ),
+    (
+        "-dl",
+        "--detect-language",
+        false,
+        "exit after automatically detecting language",
+    ),
+    ("", "--prompt", true, "initial prompt"),
+    ("-m", "--model", true, "model path"),
+    ("-f", "--file", true, "input audio file path"),
+];
+
+pub fn baseline_flags() -> Vec<FlagInfo> {
+    BASELINE_FLAGS
+        .iter()
+        .map(|(short, long, takes_value, description)| FlagInfo {
+            flag: long.to_string(),
+            short: (!short.is_empty()).then(|| short.to_string()),
+            description: description.to_string(),
+            takes_value: *takes_value,
+        })
+        .collect()
+}
+
+/// Parses option lines of the form
+/// `  -t N,      --threads N         [4      ] number of threads ...`.
+pub fn parse_help(help: &str) -> Vec<FlagInfo> {
+    let mut flags = Vec::new();
+
+    for line in help.lines() {
+        let line = line.trim();
+        if !line.starts_with('-') {
+            continue;
+        }
+
+        let (names, description) = match line.split_once('[') {
+            Some((names, rest)) => (names, rest.split_once(']').map(|(_, d)| d).unwrap_or(rest)),
+            None => match description_start(line) {
+                Some(start) => line.split_at(start),
+                None => (line, ""),
+            },
+        };
+
+        let mut short = None;
+        let mut long = None;
+        let mut takes_value = false;
+
+        for name in names.split(',') {
+            let mut parts = name.split_whitespace();
+            let Some(flag) = parts.next() else {
+                continue;
+            };
+            if !flag.starts_with('-') {
+                continue;
+            }
+            takes_value |= parts.next().is_some();
+            if flag.starts_with("--") {
+                long = Some(flag.to_string());
+            } else {
+                short = Some(flag.to_string());
+            }
+        }
+
+        let Some(flag) = long.clone().or_else(|| short.clone()) else {
+            continue;
+        };
+
+        flags.push(FlagInfo {
+            flag,
+            short: if long.is_some() { short } else { None },
+            description: description.trim().to_string(),
+            takes_value,
+        });
+    }
+
+    flags
+}
+
+/// Without a `[default]` column the description follows the first gap of
+/// two or more spaces, except the padding between `-x,` and `--long`.
+fn description_start(line: &str) -> Option<usize> {
+    line.match_indices("  ")
+        .map(|(index, _)| index)
+        .find(|&index| !line[..index].trim_end().ends_with(','))
+}
+
+/// Caches the sidecar's parsed `--help` for the rest of the session.
+#[derive(Default)]
+pub struct SidecarFlags {
+    flags: OnceCell<Vec<FlagInfo>>,
+}
+
+impl SidecarFlags {
+    pub async fn get(&self, app: &AppHandle) -> Vec<FlagInfo> {
+        match self.flags.get_or_try_init(|| probe_flags(app)).await {
+            Ok(flags) => flags.clone(),
+            Err(_) => baseline_flags(),
+        }
+    }
+}
+
+async fn probe_flags(app: &AppHandle) -> Result<Vec<FlagInfo>, String> {
+    let output = app
+        .shell()
+        .sidecar("binaries/whisper-cli")
+        .map_err(|e| format!("Failed to create sidecar command: {}", e))?
+        .args(["--help"])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run whisper-cli --help: {}", e))?;
+
+    // whisper-cli prints its usage to stderr.
+    let help = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let flags = parse_help(&help);
+    if flags.is_empty() {
+        return Err("Could not parse whisper-cli --help output".to_string());
+    }
+    Ok(flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Excerpt of `whisper-cli --help`, column alignment included.
+    const HELP: &str = "\
+usage: whisper-cli [options] file0 file1 ...
+supported audio formats: flac, mp3, ogg, wav
+
+options:
+  -h,        --help              [default] show this help message and exit
+  -t N,      --threads N         [4      ] number of threads to use during computation
+  -p N,      --processors N      [1      ] number of processors to use during computation
+  -sow,      --split-on-word     [false  ] split on word rather than on token
+  -tr,       --translate         [false  ] translate from source language to english
+  -ojf,      --output-json-full  [false  ] include more information in the JSON file
+  -l LANG,   --language LANG     [en     ] spoken language ('auto' for auto-detect)
+  -m FNAME,  --model FNAME       [models/ggml-base.en.bin] model path
+  --prompt PROMPT                [       ] initial prompt (max n_text_ctx/2 tokens)
+  --grammar GRAMMAR              [       ] GBNF grammar to guide decoding
+  --grammar-penalty N            [100.0  ] scales down logits of nongrammar tokens
+";
+
+    fn find<'a>(flags: &'a [FlagInfo], flag: &str) -> &'a FlagInfo {
+        flags
+            .iter()
+            .find(|f| f.flag == flag)
+            .unwrap_or_else(|| panic!("{} not parsed", flag))
+    }
+
+    #[test]
+    fn parses_flags_with_short_forms_and_values() {
+        let flags = parse_help(HELP);
+        let threads = find(&flags, "--threads");
+        assert_eq!(threads.short.as_deref(), Some("-t"));
+        assert!(threads.takes_value);
+        assert_eq!(
+            threads.description,
+            "number of threads to use during computation"
+        );
+
+        let model = find(&flags, "--model");
+        assert_eq!(model.short.as_deref(), Some("-m"));
+        assert!(model.takes_value);
+        assert_eq!(model.description, "model path");
+    }
+
+    #[test]
+    fn parses_long_only_flags() {
+        let flags = parse_help(HELP);
+        let grammar = find(&flags, "--grammar");
+        assert_eq!(grammar.short, None);
+        assert!(grammar.takes_value);
+        assert_eq!(grammar.description, "GBNF grammar to guide decoding");
+
+        let prompt = find(&flags, "--prompt");
+        assert_eq!(
+            prompt.description,
+            "initial prompt (max n_text_ctx/2 tokens)"
+        );
+        assert!(find(&flags, "--grammar-penalty").takes_value);
+    }
+
+    #[test]
+    fn parses_flags_without_values() {
+        let flags = parse_help(HELP);
+        for flag in [
+            "--help",
+            "--split-on-word",
+            "--translate",
+            "--output-json-full",
+        ] {
+            assert!(!find(&flags, flag).takes_value, "{}", flag);
+        }
+        assert_eq!(
+            find(&flags, "--output-json-full").short.as_deref(),
+            Some("-ojf")
+        );
+        assert_eq!(
+            find(&flags, "--help").description,
+            "show this help message and exit"
+        );
+    }
+
+    #[test]
+    fn skips_lines_that_are_not_options() {
+        let flags = parse_help(HELP);
+        assert_eq!(flags.len(), 11);
+        assert!(parse_help("usage: whisper-cli [options]\n\noptions:\n").is_empty());
+    }
+
+    #[test]
+    fn parses_options_without_a_default_column() {
+        let flags = parse_help("  -nt,       --no-timestamps    do not print timestamps\n");
+        assert_eq!(flags.len(), 1);
+        assert_eq!(flags[0].flag, "--no-timestamps");
+        assert_eq!(flags[0].short.as_deref(), Some("-nt"));
+        assert!(!flags[0].takes_value);
+    }
+}
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Large enough for any realistic command grammar while keeping a pasted
+/// document from being handed to the sampler.
+const MAX_GRAMMAR_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrammarOptions {
+    /// Inline GBNF source.
+    pub grammar: String,
+    pub rule: Option<String>,
+    pub penalty: Option<f32>,
+}
+
+impl GrammarOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.grammar.trim().is_empty() {
+            return Err("Grammar is empty".to_string());
+        }
+        if self.grammar.len() > MAX_GRAMMAR_BYTES {
+            return Err(format!(
+                "Grammar is too large: {} bytes, limit is {} bytes",
+                self.grammar.len(),
+                MAX_GRAMMAR_BYTES
+            ));
+        }
+        if self.rule.as_deref().is_some_and(|r| r.trim().is_empty()) {
+            return Err("Grammar rule name is empty".to_string());
+        }
+        if self.penalty.is_some_and(|p| !p.is_finite() || p < 0.0) {
+            return Err("Grammar penalty must be a non-negative number".to_string());
+        }
+        Ok(())
+    }
+
+    /// whisper-cli skips grammar sampling entirely when the rule isn't
+    /// found, and its default rule name is empty, so fall back to `root`.
+    pub fn rule_name(&self) -> String {
+        self.rule.clone().unwrap_or_else(|| "root".to_string())
+    }
+}
+
+/// The returned file is deleted when dropped, so keep it alive until the
+/// sidecar has exited.
+pub fn write_grammar_file(grammar: &str) -> Result<NamedTempFile, String> {
+    let mut file = tempfile::Builder::new()
+        .prefix("whisper-grammar-")
+        .suffix(".gbnf")
+        .tempfile()
+        .map_err(|e| format!("Failed to create grammar file: {}", e))?;
+
+    file.write_all(grammar.as_bytes())
+        .and_then(|_| file.flush())
+        .map_err(|e| format!("Failed to write grammar file: {}", e))?;
+
+    Ok(file)
+}
@@ -0,0 +1,180 @@
+use super::{PreflightWarning, WhisperOptions};
+use crate::audio::AudioInfo;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri_plugin_shell::process::CommandChild;
+
+/// How many launched jobs `get_job` can still look up after they finish.
+const MAX_JOB_RECORDS: usize = 200;
+
+/// Everything needed to reproduce a run, as it was actually launched.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRecord {
+    pub job_id: String,
+    pub audio_path: String,
+    pub model_path: String,
+    pub sidecar: Option<String>,
+    pub options: WhisperOptions,
+    pub argv: Vec<String>,
+    /// The original file as probed before any conversion.
+    pub audio: AudioInfo,
+    pub warnings: Vec<PreflightWarning>,
+    pub started_at_ms: u64,
+}
+
+struct ActiveJob {
+    /// Canonical path, so models referenced by name and by path compare equal.
+    model_path: PathBuf,
+    cancelled: Arc<AtomicBool>,
+    /// Set once whisper-cli has been spawned.
+    child: Option<CommandChild>,
+}
+
+#[derive(Default)]
+struct JobState {
+    /// Every accepted, unfinished job, including ones still waiting for a
+    /// worker slot.
+    active: HashMap<String, ActiveJob>,
+    records: HashMap<String, JobRecord>,
+    record_order: VecDeque<String>,
+}
+
+#[derive(Default)]
+pub struct JobRegistry {
+    state: Arc<Mutex<JobState>>,
+}
+
+/// Marks its job as finished when dropped.
+pub struct JobHandle {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+    state: Arc<Mutex<JobState>>,
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl JobRegistry {
+    pub fn register(&self, model_path: &Path) -> JobHandle {
+        let id = uuid::Uuid::new_v4().to_string();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.state.lock().unwrap().active.insert(
+            id.clone(),
+            ActiveJob {
+                model_path: canonical(model_path),
+                cancelled: cancelled.clone(),
+                child: None,
+            },
+        );
+
+        JobHandle {
+            id,
+            cancelled,
+            state: self.state.clone(),
+        }
+    }
+
+    pub fn record_started(&self, record: JobRecord) {
+        let mut state = self.state.lock().unwrap();
+        state.record_order.push_back(record.job_id.clone());
+        state.records.insert(record.job_id.clone(), record);
+
+        while state.record_order.len() > MAX_JOB_RECORDS {
+            if let Some(oldest) = state.record_order.pop_front() {
+                state.records.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<JobRecord> {
+        self.state.lock().unwrap().records.get(job_id).cloned()
+    }
+
+    /// Launched jobs `get_job` still knows about, newest first, each with
+    /// whether it is still running.
+    pub fn records(&self) -> Vec<(JobRecord, bool)> {
+        let state = self.state.lock().unwrap();
+        state
+            .record_order
+            .iter()
+            .rev()
+            .filter_map(|id| state.records.get(id))
+            .map(|record| (record.clone(), state.active.contains_key(&record.job_id)))
+            .collect()
+    }
+
+    /// Models referenced by name and by path compare equal.
+    pub fn is_model_in_use(&self, model_path: &Path) -> bool {
+        let model_path = canonical(model_path);
+        self.state
+            .lock()
+            .unwrap()
+            .active
+            .values()
+            .any(|job| job.model_path == model_path)
+    }
+
+    /// Kills the job's whisper-cli process. A job still waiting for a worker
+    /// slot is marked so it never starts.
+    pub fn cancel(&self, job_id: &str) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        let job = state
+            .active
+            .get_mut(job_id)
+            .ok_or_else(|| format!("Job '{}' is not running", job_id))?;
+
+        job.cancelled.store(true, Ordering::SeqCst);
+        if let Some(child) = job.child.take() {
+            child
+                .kill()
+                .map_err(|e| format!("Failed to stop transcription: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+impl JobHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// Stops the process without marking the job as cancelled, for callers
+    /// that have what they need before whisper-cli would exit.
+    pub fn kill_child(&self) {
+        let child = self
+            .state
+            .lock()
+            .unwrap()
+            .active
+            .get_mut(&self.id)
+            .and_then(|job| job.child.take());
+        if let Some(child) = child {
+            let _ = child.kill();
+        }
+    }
+
+    /// Keeps the spawned process so `JobRegistry::cancel` can kill it.
+    pub fn attach_child(&self, child: CommandChild) {
+        if let Some(job) = self.state.lock().unwrap().active.get_mut(&self.id) {
+            job.child = Some(child);
+        }
+    }
+}
+
+impl Drop for JobHandle {
+    fn drop(&mut self) {
+        self.state.lock().unwrap().active.remove(&self.id);
+    }
+}
@@ -0,0 +1,141 @@
+use regex::Regex;
+
+enum Pattern {
+    Word { keyword: String, folded: String },
+    Regex { keyword: String, regex: Regex },
+}
+
+/// Matches keywords against one segment at a time. Plain entries match
+/// case-insensitively on whole words; entries wrapped in slashes
+/// (`/invoices?/`) are case-insensitive regular expressions. A keyword split
+/// across two segments is not detected.
+pub struct KeywordMatcher {
+    patterns: Vec<Pattern>,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+impl KeywordMatcher {
+    pub fn new(keywords: &[String]) -> Result<Self, String> {
+        let mut patterns = Vec::new();
+
+        for keyword in keywords {
+            let trimmed = keyword.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let pattern = match trimmed
+                .strip_prefix('/')
+                .and_then(|rest| rest.strip_suffix('/'))
+            {
+                Some(expr) if !expr.is_empty() => Pattern::Regex {
+                    keyword: trimmed.to_string(),
+                    regex: Regex::new(&format!("(?i){}", expr))
+                        .map_err(|e| format!("Invalid keyword pattern '{}': {}", trimmed, e))?,
+                },
+                _ => Pattern::Word {
+                    keyword: trimmed.to_string(),
+                    folded: trimmed.to_lowercase(),
+                },
+            };
+            patterns.push(pattern);
+        }
+
+        Ok(Self { patterns })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn find_matches(&self, text: &str) -> Vec<String> {
+        let folded = text.to_lowercase();
+
+        self.patterns
+            .iter()
+            .filter_map(|pattern| match pattern {
+                Pattern::Word {
+                    keyword,
+                    folded: needle,
+                } => contains_word(&folded, needle).then(|| keyword.clone()),
+                Pattern::Regex { keyword, regex } => regex.is_match(text).then(|| keyword.clone()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(keywords: &[&str]) -> KeywordMatcher {
+        let keywords: Vec<String> = keywords.iter().map(|k| k.to_string()).collect();
+        KeywordMatcher::new(&keywords).unwrap()
+    }
+
+    #[test]
+    fn folds_unicode_case() {
+        let matcher = matcher(&["Ärger", "москва"]);
+        assert_eq!(matcher.find_matches("So viel ÄRGER heute"), ["Ärger"]);
+        assert_eq!(matcher.find_matches("Поезд в МОСКВА"), ["москва"]);
+    }
+
+    #[test]
+    fn matches_whole_words_only() {
+        let matcher = matcher(&["cat"]);
+        assert_eq!(matcher.find_matches("The cat's toy"), ["cat"]);
+        assert_eq!(matcher.find_matches("cat."), ["cat"]);
+        assert!(matcher.find_matches("concatenate").is_empty());
+        assert!(matcher.find_matches("cat_food").is_empty());
+        assert!(matcher.find_matches("catégorie").is_empty());
+    }
+
+    #[test]
+    fn matches_phrases_and_returns_keywords_in_order() {
+        let matcher = matcher(&["  Project Apollo ", "deadline"]);
+        assert_eq!(
+            matcher.find_matches("The deadline for project apollo moved"),
+            ["Project Apollo", "deadline"]
+        );
+    }
+
+    #[test]
+    fn slash_wrapped_entries_are_case_insensitive_regexes() {
+        let matcher = matcher(&["/invoices?/", "/\\bQ[1-4]\\b/"]);
+        assert_eq!(
+            matcher.find_matches("Send the INVOICE for q3"),
+            ["/invoices?/", "/\\bQ[1-4]\\b/"]
+        );
+        assert!(matcher.find_matches("quarterly report").is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_regexes() {
+        let err = KeywordMatcher::new(&["/(unclosed/".to_string()])
+            .err()
+            .unwrap();
+        assert!(
+            err.starts_with("Invalid keyword pattern '/(unclosed/':"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn skips_blank_entries_and_treats_empty_slashes_as_a_word() {
+        assert!(matcher(&["", "   "]).is_empty());
+        let matcher = matcher(&["//"]);
+        assert_eq!(matcher.find_matches("a // b"), ["//"]);
+    }
+}
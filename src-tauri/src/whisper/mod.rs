@@ -1,3 +1,29 @@
 pub mod cli;
+pub mod collect;
+pub mod detect;
+pub mod extra_args;
+pub mod flags;
+pub mod grammar;
+pub mod jobs;
+pub mod keywords;
+pub mod output;
+pub mod pool;
+pub mod preflight;
+pub mod process;
+pub mod severity;
+pub mod words;
 
 pub use cli::*;
+pub use collect::*;
+pub use detect::*;
+pub use extra_args::*;
+pub use flags::*;
+pub use grammar::*;
+pub use jobs::*;
+pub use keywords::*;
+pub use output::*;
+pub use pool::*;
+pub use preflight::*;
+pub use process::*;
+pub use severity::*;
+pub use words::*;
@@ -0,0 +1,120 @@
+use crate::subtitles::TranscriptFormat;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The formats requested from the frontend: a single name or a list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum OutputFormats {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OutputFormats {
+    /// Unknown names are rejected here; whisper-cli would only complain
+    /// about the flag after the model has loaded.
+    pub fn parse(&self) -> Result<Vec<TranscriptFormat>, String> {
+        let names = match self {
+            Self::One(name) => std::slice::from_ref(name),
+            Self::Many(names) => names.as_slice(),
+        };
+
+        let mut formats = Vec::new();
+        for name in names {
+            let format = TranscriptFormat::parse(name.trim()).ok_or_else(|| {
+                format!(
+                    "Unsupported output format '{}'; expected txt, srt, vtt or json",
+                    name
+                )
+            })?;
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+        Ok(formats)
+    }
+}
+
+pub fn output_flag(format: TranscriptFormat) -> &'static str {
+    match format {
+        TranscriptFormat::Txt => "-otxt",
+        TranscriptFormat::Srt => "-osrt",
+        TranscriptFormat::Vtt => "-ovtt",
+        TranscriptFormat::Json => "-oj",
+    }
+}
+
+/// The audio file name without its extension, inside `output_dir` when given
+/// and next to the audio otherwise.
+pub fn output_base(audio_path: &Path, output_dir: Option<&Path>) -> PathBuf {
+    let stem = audio_path.file_stem().unwrap_or_default();
+    match output_dir {
+        Some(dir) => dir.join(stem),
+        None => audio_path.with_file_name(stem),
+    }
+}
+
+/// whisper-cli appends the extension to the `-of` base itself.
+pub fn output_file_path(base: &Path, format: TranscriptFormat) -> PathBuf {
+    let mut path = base.as_os_str().to_owned();
+    path.push(".");
+    path.push(format.extension());
+    PathBuf::from(path)
+}
+
+/// The files whisper-cli wrote for `formats`, in the same order. Missing and
+/// empty files are left out, so a shorter list means something wasn't
+/// produced.
+pub fn existing_output_files(base: &Path, formats: &[TranscriptFormat]) -> Vec<String> {
+    formats
+        .iter()
+        .map(|format| output_file_path(base, *format))
+        .filter(|path| is_non_empty_file(path))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+fn is_non_empty_file(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.len() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_out_missing_and_empty_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("talk");
+        std::fs::write(output_file_path(&base, TranscriptFormat::Srt), "1\n").unwrap();
+        std::fs::write(output_file_path(&base, TranscriptFormat::Txt), "").unwrap();
+        std::fs::create_dir(output_file_path(&base, TranscriptFormat::Json)).unwrap();
+
+        let formats = [
+            TranscriptFormat::Txt,
+            TranscriptFormat::Srt,
+            TranscriptFormat::Vtt,
+            TranscriptFormat::Json,
+        ];
+        let srt = output_file_path(&base, TranscriptFormat::Srt);
+        assert_eq!(
+            existing_output_files(&base, &formats),
+            [srt.to_string_lossy().to_string()]
+        );
+        assert!(existing_output_files(&base, &[]).is_empty());
+    }
+
+    #[test]
+    fn appends_the_extension_to_the_whole_base() {
+        let base = Path::new("/out/talk.final");
+        assert_eq!(
+            output_file_path(base, TranscriptFormat::Vtt),
+            Path::new("/out/talk.final.vtt")
+        );
+        assert_eq!(
+            output_base(Path::new("/audio/talk.final.mp3"), Some(Path::new("/out"))),
+            base
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+struct PoolState {
+    count: usize,
+    /// Permits still held by running jobs that must be retired instead of
+    /// returned to the semaphore after the pool was shrunk.
+    debt: usize,
+}
+
+/// Limits how many whisper-cli processes run at once. Resizing only affects
+/// jobs that have not acquired a slot yet; running jobs keep theirs.
+pub struct WorkerPool {
+    semaphore: Arc<Semaphore>,
+    state: Arc<Mutex<PoolState>>,
+}
+
+pub struct WorkerPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    state: Arc<Mutex<PoolState>>,
+}
+
+pub fn max_worker_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// whisper-cli uses four threads per process by default, so one worker per
+/// four cores avoids oversubscribing the CPU.
+pub fn default_worker_count() -> usize {
+    (max_worker_count() / 4).max(1)
+}
+
+impl WorkerPool {
+    pub fn new(count: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(count)),
+            state: Arc::new(Mutex::new(PoolState { count, debt: 0 })),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.state.lock().unwrap().count
+    }
+
+    pub fn resize(&self, count: usize) {
+        let mut state = self.state.lock().unwrap();
+
+        if count > state.count {
+            let grow = count - state.count;
+            let repaid = grow.min(state.debt);
+            state.debt -= repaid;
+            self.semaphore.add_permits(grow - repaid);
+        } else {
+            let shrink = state.count - count;
+            let forgotten = self.semaphore.forget_permits(shrink);
+            state.debt += shrink - forgotten;
+        }
+
+        state.count = count;
+    }
+
+    /// Takes a free slot without waiting. Fails if none are free or other
+    /// callers are already waiting for one.
+    pub fn try_acquire(&self) -> Option<WorkerPermit> {
+        let permit = self.semaphore.clone().try_acquire_owned().ok()?;
+        Some(WorkerPermit {
+            permit: Some(permit),
+            state: self.state.clone(),
+        })
+    }
+
+    pub async fn acquire(&self) -> WorkerPermit {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("worker semaphore is never closed");
+
+        WorkerPermit {
+            permit: Some(permit),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl Drop for WorkerPermit {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(permit) = self.permit.take() {
+            if state.debt > 0 {
+                state.debt -= 1;
+                permit.forget();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn available(pool: &WorkerPool) -> usize {
+        pool.semaphore.available_permits()
+    }
+
+    fn debt(pool: &WorkerPool) -> usize {
+        pool.state.lock().unwrap().debt
+    }
+
+    fn acquire(pool: &WorkerPool, count: usize) -> Vec<WorkerPermit> {
+        (0..count)
+            .map(|_| pool.try_acquire().expect("a free slot"))
+            .collect()
+    }
+
+    #[test]
+    fn shrinking_below_held_permits_leaves_running_jobs_alone() {
+        let pool = WorkerPool::new(3);
+        let mut held = acquire(&pool, 3);
+
+        pool.resize(1);
+        assert_eq!(pool.count(), 1);
+        assert_eq!(debt(&pool), 2);
+        assert!(pool.try_acquire().is_none());
+
+        // The first two finished jobs pay off the debt instead of freeing a
+        // slot, so only one job runs once they are done.
+        held.pop();
+        assert_eq!((debt(&pool), available(&pool)), (1, 0));
+        held.pop();
+        assert_eq!((debt(&pool), available(&pool)), (0, 0));
+        assert!(pool.try_acquire().is_none());
+
+        held.pop();
+        assert_eq!(available(&pool), 1);
+        let _only = acquire(&pool, 1);
+        assert!(pool.try_acquire().is_none());
+    }
+
+    #[test]
+    fn growing_back_repays_the_debt_first() {
+        let pool = WorkerPool::new(2);
+        let held = acquire(&pool, 2);
+
+        pool.resize(1);
+        assert_eq!(debt(&pool), 1);
+        pool.resize(3);
+        assert_eq!((debt(&pool), available(&pool)), (0, 1));
+
+        let extra = acquire(&pool, 1);
+        assert!(pool.try_acquire().is_none());
+        drop(held);
+        drop(extra);
+        assert_eq!(available(&pool), 3);
+    }
+
+    #[test]
+    fn shrinking_takes_free_slots_before_adding_debt() {
+        let pool = WorkerPool::new(4);
+        let held = acquire(&pool, 1);
+
+        pool.resize(2);
+        assert_eq!((debt(&pool), available(&pool)), (0, 1));
+        pool.resize(1);
+        assert_eq!((debt(&pool), available(&pool)), (0, 0));
+        drop(held);
+        assert_eq!(available(&pool), 1);
+    }
+
+    #[test]
+    fn repeated_shrinks_and_grows_settle_on_the_last_count() {
+        let pool = WorkerPool::new(4);
+        let mut held = acquire(&pool, 4);
+
+        pool.resize(1);
+        pool.resize(2);
+        pool.resize(1);
+        assert_eq!(debt(&pool), 3);
+        held.clear();
+        assert_eq!((pool.count(), debt(&pool), available(&pool)), (1, 0, 1));
+
+        pool.resize(3);
+        let _held = acquire(&pool, 3);
+        assert!(pool.try_acquire().is_none());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::downloader::is_english_only;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use sysinfo::{Disks, System};
+
+/// Free space below which the output folder gets a warning. Transcripts are
+/// small, but whisper-cli fails late and unhelpfully on a full disk.
+const LOW_DISK_SPACE_MB: u64 = 100;
+
+/// Warning codes that `strict` turns into errors.
+const STRICT_CODES: &[&str] = &["low_memory", "english_only_model"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightWarning {
+    pub code: &'static str,
+    pub message: String,
+    pub params: Value,
+}
+
+/// What the checks look at, gathered once before the job starts.
+#[derive(Debug, Clone)]
+pub struct PreflightContext {
+    pub model_path: PathBuf,
+    pub language: Option<String>,
+    pub required_memory_mb: u64,
+    pub available_memory_mb: u64,
+    /// `None` when the disk holding the output folder couldn't be found.
+    pub output_free_mb: Option<u64>,
+}
+
+type Check = fn(&PreflightContext) -> Option<PreflightWarning>;
+
+const CHECKS: &[Check] = &[check_memory, check_english_only_model, check_disk_space];
+
+fn check_memory(ctx: &PreflightContext) -> Option<PreflightWarning> {
+    (ctx.available_memory_mb < ctx.required_memory_mb).then(|| PreflightWarning {
+        code: "low_memory",
+        message: format!(
+            "Transcription may be slow or fail: the model needs about {}MB but only {}MB is free",
+            ctx.required_memory_mb, ctx.available_memory_mb
+        ),
+        params: json!({
+            "required_mb": ctx.required_memory_mb,
+            "available_mb": ctx.available_memory_mb,
+        }),
+    })
+}
+
+fn check_english_only_model(ctx: &PreflightContext) -> Option<PreflightWarning> {
+    let english_only = ctx
+        .model_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|s| is_english_only(s.trim_start_matches("ggml-")));
+    if !english_only {
+        return None;
+    }
+
+    let language = ctx.language.as_deref().unwrap_or("auto");
+    (language != "en").then(|| PreflightWarning {
+        code: "english_only_model",
+        message: format!(
+            "This model only transcribes English, but the language is set to '{}'",
+            language
+        ),
+        params: json!({ "language": language }),
+    })
+}
+
+fn check_disk_space(ctx: &PreflightContext) -> Option<PreflightWarning> {
+    let free_mb = ctx.output_free_mb?;
+    (free_mb < LOW_DISK_SPACE_MB).then(|| PreflightWarning {
+        code: "low_disk_space",
+        message: format!(
+            "Only {}MB is free where the transcript will be written",
+            free_mb
+        ),
+        params: json!({ "free_mb": free_mb }),
+    })
+}
+
+pub fn available_memory_mb() -> u64 {
+    let mut system = System::new();
+    system.refresh_memory();
+    system.available_memory() / (1024 * 1024)
+}
+
+/// Free space on the disk whose mount point is the longest prefix of `path`.
+pub fn free_space_mb(path: &Path) -> Option<u64> {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space() / (1024 * 1024))
+}
+
+pub fn run_preflight(ctx: &PreflightContext) -> Vec<PreflightWarning> {
+    CHECKS.iter().filter_map(|check| check(ctx)).collect()
+}
+
+/// With `strict`, the first warning whose code is in `STRICT_CODES` becomes
+/// an error.
+pub fn enforce_strict(warnings: &[PreflightWarning], strict: bool) -> Result<(), String> {
+    if !strict {
+        return Ok(());
+    }
+    match warnings.iter().find(|w| STRICT_CODES.contains(&w.code)) {
+        Some(warning) => Err(warning.message.clone()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(model: &str) -> PreflightContext {
+        PreflightContext {
+            model_path: PathBuf::from(format!("/models/ggml-{}.bin", model)),
+            language: Some("en".to_string()),
+            required_memory_mb: 500,
+            available_memory_mb: 4000,
+            output_free_mb: Some(10_000),
+        }
+    }
+
+    fn codes(ctx: &PreflightContext) -> Vec<&'static str> {
+        run_preflight(ctx).iter().map(|w| w.code).collect()
+    }
+
+    #[test]
+    fn no_warnings_when_everything_fits() {
+        assert!(run_preflight(&context("base")).is_empty());
+    }
+
+    #[test]
+    fn warns_when_memory_is_short() {
+        let ctx = PreflightContext {
+            available_memory_mb: 300,
+            ..context("base")
+        };
+        let warnings = run_preflight(&ctx);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "low_memory");
+        assert_eq!(
+            warnings[0].params,
+            json!({ "required_mb": 500, "available_mb": 300 })
+        );
+    }
+
+    #[test]
+    fn warns_about_english_only_models_for_other_languages() {
+        let german = PreflightContext {
+            language: Some("de".to_string()),
+            ..context("base.en")
+        };
+        assert_eq!(codes(&german), ["english_only_model"]);
+
+        let auto = PreflightContext {
+            language: None,
+            ..context("small.en-q5_1")
+        };
+        let warnings = run_preflight(&auto);
+        assert_eq!(warnings[0].params, json!({ "language": "auto" }));
+
+        assert!(codes(&context("base.en")).is_empty());
+        let multilingual = PreflightContext {
+            language: Some("de".to_string()),
+            ..context("base")
+        };
+        assert!(codes(&multilingual).is_empty());
+    }
+
+    #[test]
+    fn warns_about_low_disk_space_only_when_known() {
+        let low = PreflightContext {
+            output_free_mb: Some(50),
+            ..context("base")
+        };
+        assert_eq!(codes(&low), ["low_disk_space"]);
+
+        let unknown = PreflightContext {
+            output_free_mb: None,
+            ..context("base")
+        };
+        assert!(codes(&unknown).is_empty());
+    }
+
+    #[test]
+    fn reports_every_failing_check_in_order() {
+        let ctx = PreflightContext {
+            language: Some("fr".to_string()),
+            available_memory_mb: 100,
+            output_free_mb: Some(1),
+            ..context("tiny.en")
+        };
+        assert_eq!(
+            codes(&ctx),
+            ["low_memory", "english_only_model", "low_disk_space"]
+        );
+    }
+
+    #[test]
+    fn strict_only_rejects_strict_codes() {
+        let low_disk = PreflightContext {
+            output_free_mb: Some(1),
+            ..context("base")
+        };
+        let warnings = run_preflight(&low_disk);
+        assert!(enforce_strict(&warnings, true).is_ok());
+
+        let low_memory = PreflightContext {
+            available_memory_mb: 1,
+            ..context("base")
+        };
+        let warnings = run_preflight(&low_memory);
+        assert!(enforce_strict(&warnings, false).is_ok());
+        assert_eq!(
+            enforce_strict(&warnings, true).unwrap_err(),
+            warnings[0].message
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// Tauri places sidecars next to the main executable with the target
+/// triple stripped, so this is the exact path of every whisper-cli we spawn.
+pub fn get_sidecar_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    Some(dir.join(format!("whisper-cli{}", std::env::consts::EXE_SUFFIX)))
+}
+
+/// Kills whisper-cli processes left behind by a previous session. Only
+/// processes running our exact sidecar binary are considered, and only when
+/// their parent is gone or isn't a whisper-gui executable: the parent may be
+/// this process or another running copy of the app, with live jobs.
+pub fn kill_orphaned_sidecars() -> Vec<u32> {
+    let Some(sidecar_path) = get_sidecar_path() else {
+        return Vec::new();
+    };
+    let sidecar_path = sidecar_path.canonicalize().unwrap_or(sidecar_path);
+    let app_name = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_name().map(|name| name.to_os_string()));
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::Always),
+    );
+
+    let mut killed = Vec::new();
+    for (pid, process) in system.processes() {
+        let Some(exe) = process.exe() else {
+            continue;
+        };
+        let exe = exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf());
+        if exe != sidecar_path {
+            continue;
+        }
+        // A dead parent's PID may have been reused, so the parent's
+        // executable is checked rather than just its existence.
+        let parent_is_app = process
+            .parent()
+            .and_then(|parent| system.process(parent))
+            .and_then(|parent| parent.exe())
+            .is_some_and(|exe| app_name.as_deref() == exe.file_name());
+        if parent_is_app {
+            continue;
+        }
+        if process.kill() {
+            killed.push(pid.as_u32());
+        }
+    }
+
+    killed
+}
+
+/// Kills `pid` if it is still running, for processes whose `CommandChild`
+/// has already been given up.
+pub fn kill_process(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    system.process(pid).is_some_and(|process| process.kill())
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessPriority {
+    #[default]
+    Normal,
+    Low,
+}
+
+impl ProcessPriority {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "normal" => Ok(Self::Normal),
+            "low" => Ok(Self::Low),
+            other => Err(format!(
+                "Unknown process priority '{}' (expected \"low\" or \"normal\")",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn lower_process_priority(pid: u32) -> Result<(), String> {
+    // Nice 10 leaves room for the UI while still using idle cores fully.
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, 10) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to lower process priority: {}",
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+#[cfg(windows)]
+pub fn lower_process_priority(pid: u32) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, SetPriorityClass, BELOW_NORMAL_PRIORITY_CLASS, PROCESS_SET_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return Err(format!(
+                "Failed to open process: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let ok = SetPriorityClass(handle, BELOW_NORMAL_PRIORITY_CLASS);
+        let err = std::io::Error::last_os_error();
+        CloseHandle(handle);
+        if ok == 0 {
+            return Err(format!("Failed to lower process priority: {}", err));
+        }
+    }
+    Ok(())
+}
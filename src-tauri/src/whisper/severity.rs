@@ -0,0 +1,128 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Progress,
+    Warning,
+    Error,
+}
+
+/// Case-insensitive substrings checked in order; the first match wins, so
+/// more severe patterns come first. whisper.cpp writes all of its logging to
+/// stderr, so anything unmatched is treated as info.
+const SEVERITY_PATTERNS: &[(&str, Severity)] = &[
+    // GPU builds probe for devices on startup and fall back to the CPU.
+    ("ggml_cuda_init: failed", Severity::Warning),
+    ("no gpu found", Severity::Info),
+    ("error:", Severity::Error),
+    ("failed to", Severity::Error),
+    ("failed with", Severity::Error),
+    ("unknown argument", Severity::Error),
+    ("invalid argument", Severity::Error),
+    ("not found", Severity::Error),
+    ("out of memory", Severity::Error),
+    ("segmentation fault", Severity::Error),
+    ("abort", Severity::Error),
+    ("warning:", Severity::Warning),
+    ("warn:", Severity::Warning),
+    ("deprecated", Severity::Warning),
+    ("falling back", Severity::Warning),
+    ("progress =", Severity::Progress),
+    ("whisper_print_progress_callback", Severity::Progress),
+    ("processing '", Severity::Progress),
+];
+
+pub fn classify_stderr(line: &str) -> Severity {
+    let lower = line.to_ascii_lowercase();
+    SEVERITY_PATTERNS
+        .iter()
+        .find(|(pattern, _)| lower.contains(pattern))
+        .map(|(_, severity)| *severity)
+        .unwrap_or(Severity::Info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lines as whisper-cli prints them, from model load to timings.
+    const FIXTURES: &[(&str, Severity)] = &[
+        (
+            "whisper_init_from_file_with_params_no_state: loading model from 'models/ggml-base.en.bin'",
+            Severity::Info,
+        ),
+        ("whisper_model_load: n_vocab       = 51864", Severity::Info),
+        ("whisper_model_load:      CPU total size =   147.37 MB", Severity::Info),
+        ("whisper_backend_init_gpu: no GPU found", Severity::Info),
+        (
+            "ggml_cuda_init: failed to initialize CUDA: no CUDA-capable device is detected",
+            Severity::Warning,
+        ),
+        (
+            "system_info: n_threads = 4 / 8 | AVX = 1 | AVX2 = 1 | AVX512 = 0 | FMA = 1 | NEON = 0 |",
+            Severity::Info,
+        ),
+        (
+            "main: WARNING: model is not multilingual, ignoring language and translation options",
+            Severity::Warning,
+        ),
+        (
+            "main: processing 'samples/jfk.wav' (176000 samples, 11.0 sec), 4 threads, 1 processors, 5 beams + best of 5, lang = en, task = transcribe, timestamps = 1 ...",
+            Severity::Progress,
+        ),
+        (
+            "whisper_print_progress_callback: progress =  40%",
+            Severity::Progress,
+        ),
+        (
+            "whisper_full_with_state: auto-detected language: en (p = 0.977899)",
+            Severity::Info,
+        ),
+        ("whisper_print_timings:     load time =    59.67 ms", Severity::Info),
+        (
+            "whisper_print_timings:     fallbacks =   0 p /   0 h",
+            Severity::Info,
+        ),
+        (
+            "error: failed to read audio file 'samples/missing.wav'",
+            Severity::Error,
+        ),
+        ("error: unknown argument: --no-such-flag", Severity::Error),
+        (
+            "whisper_init_from_file_with_params_no_state: failed to open 'models/ggml-huge.bin'",
+            Severity::Error,
+        ),
+        ("error: failed to initialize whisper context", Severity::Error),
+        (
+            "ggml_backend_cpu_buffer_type_alloc_buffer: failed to allocate buffer of size 3094623264",
+            Severity::Error,
+        ),
+    ];
+
+    #[test]
+    fn classifies_real_whisper_cli_output() {
+        for (line, expected) in FIXTURES {
+            assert_eq!(classify_stderr(line), *expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        assert_eq!(classify_stderr("ERROR: out of memory"), Severity::Error);
+        assert_eq!(
+            classify_stderr("Warning: low disk space"),
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn unmatched_and_blank_lines_are_info() {
+        assert_eq!(classify_stderr(""), Severity::Info);
+        assert_eq!(
+            classify_stderr("whisper_model_load: type          = 2 (base)"),
+            Severity::Info
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WordTiming {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Mean of the probabilities of the tokens that make up the word.
+    pub probability: f32,
+}
+
+#[derive(Deserialize)]
+struct FullJson {
+    transcription: Vec<FullSegment>,
+}
+
+#[derive(Deserialize)]
+struct FullSegment {
+    #[serde(default)]
+    tokens: Vec<FullToken>,
+}
+
+#[derive(Deserialize)]
+struct FullToken {
+    text: String,
+    offsets: Offsets,
+    p: f32,
+}
+
+#[derive(Deserialize)]
+struct Offsets {
+    from: u64,
+    to: u64,
+}
+
+/// Control tokens such as `[_BEG_]` and `[_TT_150]` carry no text.
+fn is_special_token(text: &str) -> bool {
+    text.starts_with("[_") && text.ends_with(']')
+}
+
+/// Builds word timings from whisper-cli's `-ojf` output. Tokens are pieces of
+/// words; one starting with a space begins a new word, the rest extend the
+/// current one.
+pub fn parse_word_timings(contents: &str) -> Result<Vec<WordTiming>, String> {
+    let json: FullJson = serde_json::from_str(contents)
+        .map_err(|e| format!("Failed to parse whisper-cli JSON output: {}", e))?;
+
+    let mut words = Vec::new();
+    for segment in json.transcription {
+        let mut current: Option<(WordTiming, usize)> = None;
+        for token in segment.tokens {
+            if is_special_token(&token.text) || token.text.trim().is_empty() {
+                continue;
+            }
+
+            match current.as_mut() {
+                Some((word, count)) if !token.text.starts_with(' ') => {
+                    word.word.push_str(&token.text);
+                    word.end_ms = token.offsets.to;
+                    word.probability += token.p;
+                    *count += 1;
+                }
+                _ => {
+                    words.extend(current.take().map(finish_word));
+                    current = Some((
+                        WordTiming {
+                            word: token.text.trim_start().to_string(),
+                            start_ms: token.offsets.from,
+                            end_ms: token.offsets.to,
+                            probability: token.p,
+                        },
+                        1,
+                    ));
+                }
+            }
+        }
+        // Words never span segments.
+        words.extend(current.take().map(finish_word));
+    }
+    Ok(words)
+}
+
+fn finish_word((mut word, count): (WordTiming, usize)) -> WordTiming {
+    word.probability /= count as f32;
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed from `whisper-cli -ojf` output: two segments, with the control
+    /// tokens whisper adds around them.
+    const FULL_JSON: &str = r#"{
+        "systeminfo": "AVX = 1 | AVX2 = 1",
+        "model": {"type": "base", "multilingual": true},
+        "params": {"model": "models/ggml-base.bin", "language": "en", "translate": false},
+        "result": {"language": "en"},
+        "transcription": [
+            {
+                "timestamps": {"from": "00:00:00,000", "to": "00:00:02,000"},
+                "offsets": {"from": 0, "to": 2000},
+                "text": " Hello wonderful world.",
+                "tokens": [
+                    {"text": "[_BEG_]", "timestamps": {"from": "00:00:00,000", "to": "00:00:00,000"}, "offsets": {"from": 0, "to": 0}, "id": 50364, "p": 0.98, "t_dtw": -1},
+                    {"text": " Hello", "timestamps": {"from": "00:00:00,000", "to": "00:00:00,400"}, "offsets": {"from": 0, "to": 400}, "id": 2425, "p": 0.75, "t_dtw": -1},
+                    {"text": " wonder", "timestamps": {"from": "00:00:00,400", "to": "00:00:00,800"}, "offsets": {"from": 400, "to": 800}, "id": 2278, "p": 0.5, "t_dtw": -1},
+                    {"text": "ful", "timestamps": {"from": "00:00:00,800", "to": "00:00:01,100"}, "offsets": {"from": 800, "to": 1100}, "id": 906, "p": 1.0, "t_dtw": -1},
+                    {"text": " world", "timestamps": {"from": "00:00:01,100", "to": "00:00:01,800"}, "offsets": {"from": 1100, "to": 1800}, "id": 1002, "p": 0.25, "t_dtw": -1},
+                    {"text": ".", "timestamps": {"from": "00:00:01,800", "to": "00:00:02,000"}, "offsets": {"from": 1800, "to": 2000}, "id": 13, "p": 0.75, "t_dtw": -1},
+                    {"text": "[_TT_100]", "timestamps": {"from": "00:00:02,000", "to": "00:00:02,000"}, "offsets": {"from": 2000, "to": 2000}, "id": 50464, "p": 0.4, "t_dtw": -1}
+                ]
+            },
+            {
+                "timestamps": {"from": "00:00:02,000", "to": "00:00:03,000"},
+                "offsets": {"from": 2000, "to": 3000},
+                "text": "again",
+                "tokens": [
+                    {"text": "[_BEG_]", "timestamps": {"from": "00:00:02,000", "to": "00:00:02,000"}, "offsets": {"from": 2000, "to": 2000}, "id": 50364, "p": 0.9, "t_dtw": -1},
+                    {"text": "again", "timestamps": {"from": "00:00:02,000", "to": "00:00:02,600"}, "offsets": {"from": 2000, "to": 2600}, "id": 797, "p": 0.5, "t_dtw": -1},
+                    {"text": " ", "timestamps": {"from": "00:00:02,600", "to": "00:00:03,000"}, "offsets": {"from": 2600, "to": 3000}, "id": 220, "p": 0.1, "t_dtw": -1},
+                    {"text": "[_TT_150]", "timestamps": {"from": "00:00:03,000", "to": "00:00:03,000"}, "offsets": {"from": 3000, "to": 3000}, "id": 50514, "p": 0.3, "t_dtw": -1}
+                ]
+            }
+        ]
+    }"#;
+
+    fn summary(words: &[WordTiming]) -> Vec<(&str, u64, u64, f32)> {
+        words
+            .iter()
+            .map(|w| (w.word.as_str(), w.start_ms, w.end_ms, w.probability))
+            .collect()
+    }
+
+    #[test]
+    fn joins_tokens_into_words_and_skips_control_tokens() {
+        let words = parse_word_timings(FULL_JSON).unwrap();
+        assert_eq!(
+            summary(&words),
+            vec![
+                ("Hello", 0, 400, 0.75),
+                ("wonderful", 400, 1100, 0.75),
+                ("world.", 1100, 2000, 0.5),
+                ("again", 2000, 2600, 0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn words_stop_at_segment_boundaries() {
+        // "again" has no leading space, but a new segment still starts a
+        // new word rather than extending "world.".
+        let words = parse_word_timings(FULL_JSON).unwrap();
+        assert_eq!(words[2].word, "world.");
+        assert_eq!(words[3].start_ms, 2000);
+    }
+
+    #[test]
+    fn segments_without_tokens_give_no_words() {
+        let json = r#"{"transcription": [{"offsets": {"from": 0, "to": 1000}, "text": " Hi"}]}"#;
+        assert!(parse_word_timings(json).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_output_that_is_not_full_json() {
+        let err = parse_word_timings(r#"{"text": "Hi"}"#).unwrap_err();
+        assert!(err.starts_with("Failed to parse whisper-cli JSON output"));
+    }
+}
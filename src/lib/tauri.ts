@@ -25,12 +25,47 @@ export interface DownloadProgress {
 export interface TranscriptionOutput {
   line: string;
   is_error: boolean;
+  severity: "info" | "progress" | "warning" | "error";
+}
+
+export interface TranscriptionOutputBatch {
+  lines: TranscriptionOutput[];
+  segments: TranscriptSegment[];
+}
+
+export interface TranscriptSegment {
+  index: number;
+  start_ms: number;
+  end_ms: number;
+  text: string;
+}
+
+export interface WordTiming {
+  word: string;
+  start_ms: number;
+  end_ms: number;
+  probability: number;
+}
+
+export interface DetectedLanguage {
+  language: string;
+  confidence: number;
+}
+
+export interface LanguageDetectionStarted {
+  job_id: string;
+  audio_path: string;
 }
 
 export interface TranscriptionComplete {
   success: boolean;
   output: string;
+  segments: TranscriptSegment[];
+  output_files: string[];
+  words: WordTiming[];
+  detected_language: DetectedLanguage | null;
   error: string | null;
+  cancelled: boolean;
 }
 
 export async function listModels(): Promise<ModelStatus[]> {
@@ -52,7 +87,7 @@ export async function deleteModel(modelName: string): Promise<void> {
 export async function transcribeAudio(
   audioPath: string,
   modelName: string,
-  outputFormat: string,
+  outputFormat: string | string[],
   language: string | null
 ): Promise<void> {
   return invoke<void>("transcribe_audio", {
@@ -84,6 +119,25 @@ export async function selectAudioFile(): Promise<string | null> {
   return null;
 }
 
+export function onLanguageDetectionStarted(
+  callback: (started: LanguageDetectionStarted) => void
+): Promise<UnlistenFn> {
+  return listen<LanguageDetectionStarted>(
+    "language-detection-started",
+    (event) => {
+      callback(event.payload);
+    }
+  );
+}
+
+export function onInvalidCertsWarning(
+  callback: (warning: string) => void
+): Promise<UnlistenFn> {
+  return listen<string>("invalid-certs-warning", (event) => {
+    callback(event.payload);
+  });
+}
+
 export function onDownloadProgress(
   callback: (progress: DownloadProgress) => void
 ): Promise<UnlistenFn> {
@@ -92,12 +146,26 @@ export function onDownloadProgress(
   });
 }
 
-export function onTranscriptionOutput(
+export async function onTranscriptionOutput(
   callback: (output: TranscriptionOutput) => void
 ): Promise<UnlistenFn> {
-  return listen<TranscriptionOutput>("transcription-output", (event) => {
-    callback(event.payload);
-  });
+  const unlistenBatch = await listen<TranscriptionOutputBatch>(
+    "transcription-output-batch",
+    (event) => {
+      event.payload.lines.forEach(callback);
+    }
+  );
+  const unlistenLine = await listen<TranscriptionOutput>(
+    "transcription-output",
+    (event) => {
+      callback(event.payload);
+    }
+  );
+
+  return () => {
+    unlistenBatch();
+    unlistenLine();
+  };
 }
 
 export function onTranscriptionComplete(